@@ -2,7 +2,7 @@ use std::{collections::HashMap, path::Path, path::PathBuf, process::Command};
 
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 
-use yapall::{analysis::pointer, pointer::Options, Module, Operand, Signatures};
+use yapall::{analysis::pointer, context::CallSite, pointer::Options, Module, Operand, Signatures};
 
 // ------------------------------------------------------------------
 // Helpers (TODO: copied from tests)
@@ -95,68 +95,85 @@ fn module(program: &str, dir: &str, opt: u8) -> (Module, Signatures) {
 
 // ------------------------------------------------------------------
 
-const OPTS: Options = Options {
-    check_assertions: false,
-    check_strict: false,
-    contexts: 0,
-    debug: false,
-    metrics: false,
-    unification: false,
-};
+fn opts() -> Options {
+    Options {
+        check_assertions: false,
+        check_strict: false,
+        contexts: 0,
+        debug: false,
+        field_sensitive: false,
+        function_contexts: HashMap::new(),
+        heap_contexts: 0,
+        library: false,
+        metrics: false,
+        opaque_externs: false,
+        sound_externs: false,
+        unification: false,
+        widening: None,
+    }
+}
 
 pub fn any_o0(c: &mut Criterion) {
     let (m, sigs) = module("any.rs", "templates", 0);
+    let opts = opts();
     c.bench_function("pointer::analysis(any-O0)", |b| {
-        b.iter(|| pointer::analysis(black_box(&m), &sigs, &OPTS))
+        b.iter(|| pointer::analysis(black_box(&m), &sigs, &opts, &CallSite))
     });
 }
 
 pub fn any_o1(c: &mut Criterion) {
     let (m, sigs) = module("any.rs", "templates", 1);
+    let opts = opts();
     c.bench_function("pointer::analysis(any-O1)", |b| {
-        b.iter(|| pointer::analysis(black_box(&m), &sigs, &OPTS))
+        b.iter(|| pointer::analysis(black_box(&m), &sigs, &opts, &CallSite))
     });
 }
 
 pub fn cfg_o0(c: &mut Criterion) {
     let (m, sigs) = module("cfg-test.c", "property", 0);
+    let opts = opts();
     c.bench_function("pointer::analysis(cfg-O0)", |b| {
-        b.iter(|| pointer::analysis(black_box(&m), &sigs, &OPTS))
+        b.iter(|| pointer::analysis(black_box(&m), &sigs, &opts, &CallSite))
     });
 }
 
 pub fn cfg_o1(c: &mut Criterion) {
     let (m, sigs) = module("cfg-test.c", "property", 1);
+    let opts = opts();
     c.bench_function("pointer::analysis(cfg-O1)", |b| {
-        b.iter(|| pointer::analysis(black_box(&m), &sigs, &OPTS))
+        b.iter(|| pointer::analysis(black_box(&m), &sigs, &opts, &CallSite))
     });
 }
 
 pub fn linkedlist_o0(c: &mut Criterion) {
     let (m, sigs) = module("linkedlist.c", "property", 0);
+    let opts = opts();
     c.bench_function("pointer::analysis(linkedlist-O0)", |b| {
-        b.iter(|| pointer::analysis(black_box(&m), &sigs, &OPTS))
+        b.iter(|| pointer::analysis(black_box(&m), &sigs, &opts, &CallSite))
     });
 }
 
 pub fn linkedlist_o1(c: &mut Criterion) {
     let (m, sigs) = module("linkedlist.c", "property", 1);
+    let opts = opts();
     c.bench_function("pointer::analysis(linkedlist-O1)", |b| {
-        b.iter(|| pointer::analysis(black_box(&m), &sigs, &OPTS))
+        b.iter(|| pointer::analysis(black_box(&m), &sigs, &opts, &CallSite))
     });
 }
 
 pub fn vector_o0(c: &mut Criterion) {
     let (m, sigs) = module("vector.cpp", "templates", 0);
+    let opts = opts();
     c.bench_function("pointer::analysis(vector-O0)", |b| {
-        b.iter(|| pointer::analysis(black_box(&m), &sigs, &OPTS))
+        b.iter(|| pointer::analysis(black_box(&m), &sigs, &opts, &CallSite))
     });
 }
 
 pub fn vector_o1(c: &mut Criterion) {
     let (m, sigs) = module("vector.cpp", "templates", 1);
+    let opts = opts();
     c.bench_function("pointer::analysis(vector-O1)", |b| {
-        b.iter(|| pointer::analysis(black_box(&m), &sigs, &OPTS))
+        b.iter(|| pointer::analysis(black_box(&m), &sigs, &opts, &CallSite))
     });
 }
 
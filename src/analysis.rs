@@ -2,4 +2,8 @@
 pub mod callgraph;
 pub use callgraph::*;
 pub mod int;
+pub mod introspect;
 pub mod pointer;
+pub mod summary;
+pub mod unification;
+pub mod widen;
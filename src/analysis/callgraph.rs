@@ -3,7 +3,6 @@
 //!
 //! TODO:
 //!
-//! - Handle global aliases?
 //! - Fix unsoundness around certain constants (marked with TODO)
 //! - Perhaps incorporate function argument types?
 //! - Only add indirect call targets for functions that have their address taken?
@@ -17,15 +16,25 @@ use crate::{
     llvm::{Callee, FunctionName, InstructionName, Module, Operand, TerminatorOpcode},
 };
 
-fn constant_functions(c: &Constant) -> Vec<UArc<FunctionName>> {
+pub(crate) fn constant_functions(module: &Module, c: &Constant) -> Vec<UArc<FunctionName>> {
     match c {
         Constant::Function(f) => vec![f.clone()],
-        Constant::BitCast(b) => constant_functions(&b.pointer),
-        c @ Constant::GetElementPtr(_) => {
-            c.pointers().iter().flat_map(constant_functions).collect()
-        }
+        Constant::BitCast(b) => constant_functions(module, &b.pointer),
+        c @ (Constant::ExtractElement(_)
+        | Constant::ExtractValue(_)
+        | Constant::GetElementPtr(_)
+        | Constant::InsertElement(_)
+        | Constant::InsertValue(_)
+        | Constant::ShuffleVector(_)) => c
+            .pointers()
+            .iter()
+            .flat_map(|p| constant_functions(module, p))
+            .collect(),
         // No `_` pattern to ensure this is updated if the type changes
-        Constant::Global(_) => Vec::new(),
+        Constant::Global(g) => match module.aliases.get(g) {
+            Some(aliasee) => constant_functions(module, aliasee),
+            None => Vec::new(),
+        },
         Constant::Int { .. } => Vec::new(),
         Constant::Null => Vec::new(),
         Constant::Add => Vec::new(),
@@ -46,11 +55,6 @@ fn constant_functions(c: &Constant) -> Vec<UArc<FunctionName>> {
         Constant::FMul => Vec::new(),
         Constant::FDiv => Vec::new(),
         Constant::FRem => Vec::new(),
-        Constant::ExtractElement => Vec::new(), // TODO
-        Constant::InsertElement => Vec::new(),
-        Constant::ShuffleVector => Vec::new(),
-        Constant::ExtractValue => Vec::new(), // TODO
-        Constant::InsertValue => Vec::new(),
         Constant::Trunc => Vec::new(),
         Constant::ZExt => Vec::new(),
         Constant::SExt => Vec::new(),
@@ -68,6 +72,7 @@ fn constant_functions(c: &Constant) -> Vec<UArc<FunctionName>> {
         Constant::Select => Vec::new(), // TODO
         Constant::Array(_) => Vec::new(),
         Constant::Struct(_) => Vec::new(),
+        Constant::Vector(_) => Vec::new(),
         Constant::Undef => Vec::new(), // TODO
         Constant::Other => Vec::new(), // TODO
     }
@@ -117,7 +122,7 @@ pub(crate) fn call_targets(
                 Vec::new()
             }
             Operand::Constant(c) => {
-                let mut fs = constant_functions(c);
+                let mut fs = constant_functions(module, c);
                 assert!(!fs.is_empty());
                 fs.shrink_to_fit();
                 fs
@@ -141,6 +146,7 @@ pub fn analysis(module: &Module) -> HashMap<UArc<InstructionName>, Vec<UArc<Func
                     m.insert(b.terminator.name.clone(), targets);
                 }
                 // No `_` pattern to ensure this is updated if the type changes
+                TerminatorOpcode::Resume(_) => (),
                 TerminatorOpcode::Ret(_) => (),
                 TerminatorOpcode::Other => (),
             };
@@ -154,16 +160,25 @@ pub fn analysis(module: &Module) -> HashMap<UArc<InstructionName>, Vec<UArc<Func
                     // No `_` pattern to ensure this is updated if the type changes
                     Opcode::Add(_) => (),
                     Opcode::Alloca(_) => (),
+                    Opcode::AtomicRMW(_) => (),
                     Opcode::BitCast(_) => (),
+                    Opcode::CmpXchg(_) => (),
+                    Opcode::ExtractElement(_) => (),
+                    Opcode::ExtractValue(_) => (),
                     Opcode::GetElementPtr(_) => (),
                     Opcode::Icmp(_) => (),
+                    Opcode::InsertElement(_) => (),
+                    Opcode::InsertValue(_) => (),
                     Opcode::IntToPtr(_) => (),
+                    Opcode::LandingPad => (),
                     Opcode::Load(_) => (),
                     Opcode::Phi(_) => (),
                     Opcode::PtrToInt(_) => (),
                     Opcode::Select(_) => (),
+                    Opcode::ShuffleVector(_) => (),
                     Opcode::Store(_) => (),
                     Opcode::Sub(_) => (),
+                    Opcode::VAArg(_) => (),
                     Opcode::Other => (),
                 };
             }
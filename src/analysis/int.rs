@@ -174,7 +174,7 @@ pub fn analysis<'module>(
 
         call(term, op, Arc::new(args.clone())) <--
           terminator_opcode!(term, opcode),
-          if let TerminatorOpcode::Invoke(Invoke{callee, args}) = &**opcode.as_ref(),
+          if let TerminatorOpcode::Invoke(Invoke{callee, args, ..}) = &**opcode.as_ref(),
           if let Callee::Operand(op) = callee;
 
 
@@ -311,13 +311,23 @@ pub fn analysis<'module>(
           reachable_instruction_opcode!(ctx, instr, opcode),
           if matches!(&**opcode.as_ref(),
                Opcode::Alloca(_) |
+                 Opcode::AtomicRMW(_) |
+                 Opcode::CmpXchg(_) |
+                 // TODO: more accurate model
+                 Opcode::ExtractElement(_) |
+                 Opcode::ExtractValue(_) |
                  Opcode::GetElementPtr(_) |
                  // TODO: more accurate model
                  Opcode::Icmp(_) |
+                 Opcode::InsertElement(_) |
+                 Opcode::InsertValue(_) |
+                 Opcode::LandingPad |
                  // Memory is a black box, who knows what's could be in there!
                  Opcode::Load(_) |
                  Opcode::Other |
-                 Opcode::Store(_)
+                 Opcode::ShuffleVector(_) |
+                 Opcode::Store(_) |
+                 Opcode::VAArg(_)
           );
 
         // ----------------------------------------------------------
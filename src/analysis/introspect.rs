@@ -0,0 +1,80 @@
+// SPDX-License-Identifier: BSD-3-Clause
+//! Introspective context sensitivity
+//!
+//! Chooses per-function context depths (see `pointer::Options::function_contexts`)
+//! using the results of a cheap, context-insensitive pre-analysis. Functions
+//! that are likely to be expensive to analyze context-sensitively, i.e., that
+//! have large points-to sets or many callers, are analyzed without contexts.
+//! See "Introspective Analysis: Context-Sensitivity, Across the Board"
+//! (Smaragdakis et al., PLDI 2014).
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    analysis::pointer::OutputRelations,
+    arc::UArc,
+    llvm::{FunctionName, InstructionName, Module, Operand},
+};
+
+/// Cost metrics of a function, computed from a context-insensitive analysis
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Cost {
+    /// Total size of the points-to sets of the function's parameters and
+    /// instructions
+    pub points_to: usize,
+    /// Number of call sites that may call the function
+    pub callers: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Thresholds {
+    pub max_points_to: usize,
+    pub max_callers: usize,
+}
+
+impl Cost {
+    pub fn under(&self, thresholds: &Thresholds) -> bool {
+        self.points_to <= thresholds.max_points_to && self.callers <= thresholds.max_callers
+    }
+}
+
+pub fn costs(out: &OutputRelations) -> HashMap<UArc<FunctionName>, Cost> {
+    let mut costs: HashMap<UArc<FunctionName>, Cost> = HashMap::new();
+    let mut seen = HashSet::new();
+    for (_, op, alloc) in &out.operand_points_to {
+        if let Operand::Local(local) = &**op {
+            // Contexts are ignored
+            if seen.insert((op, alloc)) {
+                costs
+                    .entry(local.parent_function().clone())
+                    .or_default()
+                    .points_to += 1;
+            }
+        }
+    }
+    let callers: HashSet<&(UArc<InstructionName>, UArc<FunctionName>)> =
+        out.callgraph.iter().collect();
+    for (_, callee) in callers {
+        costs.entry(callee.clone()).or_default().callers += 1;
+    }
+    costs
+}
+
+/// Depth `contexts` for functions with costs under the thresholds, zero for
+/// the others. `out` should be the result of a context-insensitive analysis.
+pub fn depths(
+    module: &Module,
+    out: &OutputRelations,
+    contexts: u8,
+    thresholds: &Thresholds,
+) -> HashMap<UArc<FunctionName>, u8> {
+    let costs = costs(out);
+    module
+        .functions
+        .keys()
+        .map(|f| {
+            let cheap = costs.get(f).is_none_or(|c| c.under(thresholds));
+            (f.clone(), if cheap { contexts } else { 0 })
+        })
+        .collect()
+}
@@ -1,5 +1,4 @@
 // SPDX-License-Identifier: BSD-3-Clause
-// TODO: Handle global aliases
 use std::collections::{HashMap, HashSet};
 
 #[cfg(not(feature = "par"))]
@@ -10,19 +9,25 @@ use ascent::ascent_run_par as ascent_run;
 use tracing::trace_span;
 
 use crate::{
-    alloc::{Alloc, FunctionAlloc, GlobalAlloc, HeapAlloc, StackAlloc},
-    analysis::callgraph::indirect_call_targets,
+    alloc::{Alloc, FunctionAlloc, GlobalAlloc, HeapAlloc, OpaqueAlloc, StackAlloc},
+    analysis::{
+        callgraph::indirect_call_targets,
+        summary,
+        widen::{Widener, Widening},
+    },
     arc::{Arc, UArc},
+    context::{Context, ContextPolicy},
     hash::PreHashed,
     klimited::KLimited,
     llvm::constant::Constant,
     llvm::instruction::{
-        Add, BitCast, Call, GetElementPtr, IntToPtr, Load, Opcode, Phi, PtrToInt, Select, Store,
-        Sub,
+        Add, AtomicRMW, BitCast, Call, CmpXchg, ExtractElement, ExtractValue, GetElementPtr,
+        InsertElement, InsertValue, IntToPtr, Load, Opcode, Phi, PtrToInt, Select, ShuffleVector,
+        Store, Sub, VAArg,
     },
     llvm::{
-        Callee, FunctionName, GlobalName, InstructionName, InstructionOperand, Invoke, Module,
-        Operand, TerminatorOpcode,
+        BlockName, Callee, FunctionName, GlobalName, InstructionName, InstructionOperand, Invoke,
+        Module, Operand, TerminatorOpcode,
     },
     signatures::{AllocType, Signature, Signatures},
 };
@@ -31,14 +36,12 @@ use crate::{
 #[derive(Debug)]
 pub struct OutputRelations {
     pub alloc_points_to: Vec<(Arc<Alloc>, Arc<Alloc>)>,
-    pub operand_points_to: Vec<(
-        Arc<KLimited<UArc<InstructionName>>>,
-        Arc<Operand>,
-        Arc<Alloc>,
-    )>,
+    pub operand_points_to: Vec<(Arc<Context>, Arc<Operand>, Arc<Alloc>)>,
     pub reachable: Vec<UArc<FunctionName>>,
-    pub calls:
-        HashMap<(Arc<KLimited<UArc<InstructionName>>>, UArc<InstructionName>), UArc<FunctionName>>,
+    /// Context-insensitive callgraph, i.e., edges from call-like instructions
+    /// to possible callees
+    pub callgraph: Vec<(UArc<InstructionName>, UArc<FunctionName>)>,
+    pub calls: HashMap<(Arc<Context>, UArc<InstructionName>), UArc<FunctionName>>,
     pub needs_signature: Vec<UArc<FunctionName>>,
     pub metrics: Option<Metrics>,
 }
@@ -85,6 +88,13 @@ pub struct Metrics {
     /// to model complex language features; more precise models should be
     /// preferred.
     pub points_to_top: usize,
+    /// Operands that point to opaque allocations, see
+    /// `Options::opaque_externs`. Like `Top`, but each stands only for the
+    /// memory returned by one call to an unknown function.
+    pub points_to_opaque: usize,
+    /// Points-to sets of operands (in some context) and allocations that
+    /// were collapsed for exceeding the budget of `Options::widening`
+    pub widened: usize,
 }
 
 #[derive(Debug)]
@@ -93,8 +103,33 @@ pub struct Options {
     pub check_strict: bool,
     pub contexts: u8,
     pub debug: bool,
+    /// Distinguish sub-objects (e.g., struct fields) of allocations, using the
+    /// offsets computed by `getelementptr` instructions
+    pub field_sensitive: bool,
+    /// Per-function context depths, overriding `contexts`
+    pub function_contexts: HashMap<UArc<FunctionName>, u8>,
+    /// Heap context depth, i.e., how much of the calling context is used to
+    /// distinguish heap and stack allocations (heap cloning). At most
+    /// `contexts` is effective, since that's all the context there is.
+    pub heap_contexts: u8,
+    /// Analyze the module as a library: functions with external linkage are
+    /// entry points, and their pointer parameters point to placeholder
+    /// allocations (see [`crate::analysis::summary`])
+    pub library: bool,
     pub metrics: bool,
+    /// Give the pointer returned by each call to a function with unknown
+    /// effects (e.g., without a signature) its own opaque allocation, rather
+    /// than `Top`. It becomes `Top` once it escapes to unknown code, i.e., is
+    /// passed to such a function or stored into `Top`. This assumes that such
+    /// functions don't return memory they've been given.
+    pub opaque_externs: bool,
+    /// Model functions without signatures as having unknown effects (see
+    /// [`Signature::Unknown`]), rather than just returning `Top`
+    pub sound_externs: bool,
     pub unification: bool,
+    /// Collapse points-to sets grown by loads and stores past a size budget,
+    /// see [`crate::analysis::widen`]
+    pub widening: Option<Widening>,
 }
 
 // Profiling machinery
@@ -108,15 +143,42 @@ fn count(relation: &str, rule: &str) -> bool {
     true
 }
 
+/// Whether one path of `extractvalue`/`insertvalue` indices is a prefix of the
+/// other, i.e., whether they may select overlapping parts of an aggregate
+fn overlapping(p: &[u32], q: &[u32]) -> bool {
+    p.iter().zip(q).all(|(x, y)| x == y)
+}
+
 /// Pointer analysis
 ///
 /// Sources of unsoundness:
 ///
-/// - External functions without signatures
+/// - External functions without signatures, unless `Options::sound_externs`
+///   is set. Even then, they're assumed not to store pointers to their
+///   arguments' pointees anywhere but into those pointees, e.g., globals.
 /// - Signatures not expressive enough to model external functions (e.g.,
-///   `getline`, functions that take a callback)
-/// - C++ exceptions
-/// - Variable-arity functions
+///   `strtol`, which stores a pointer derived from one argument into another)
+/// - C++ exceptions thrown by external functions called outside of `invoke`,
+///   or whose signatures include `nothrow`
+pub fn analysis<P: ContextPolicy>(
+    module: &Module,
+    signatures: &Signatures,
+    opts: &Options,
+    policy: &P,
+) -> OutputRelations {
+    // Calls whose receiver may point to nothing get receiver-insensitive
+    // edges. Whether a points-to set is empty isn't known until the end, so
+    // rerun with those calls until there are no new ones.
+    let mut no_receiver = HashSet::new();
+    loop {
+        let (outs, empty) = run(module, signatures, opts, policy, &no_receiver);
+        if empty.is_empty() {
+            return outs;
+        }
+        no_receiver.extend(empty);
+    }
+}
+
 #[allow(clippy::collapsible_if)]
 #[allow(clippy::collapsible_match)]
 #[allow(clippy::diverging_sub_expression)]
@@ -125,11 +187,13 @@ fn count(relation: &str, rule: &str) -> bool {
 #[allow(clippy::never_loop)]
 #[allow(clippy::type_complexity)]
 #[allow(clippy::unused_unit)]
-pub fn analysis<'module>(
+fn run<'module, P: ContextPolicy>(
     module: &'module Module,
     signatures: &Signatures,
     opts: &Options,
-) -> OutputRelations {
+    policy: &P,
+    no_receiver: &HashSet<InstructionOperand>,
+) -> (OutputRelations, HashSet<InstructionOperand>) {
     {
         #![allow(clippy::nonminimal_bool)]
         debug_assert!(!(opts.check_strict && !opts.check_assertions));
@@ -145,43 +209,82 @@ pub fn analysis<'module>(
         false,
         None,
     )));
-    let optarg_alloc = Arc::new(Alloc::Global(GlobalAlloc::new(
-        Arc::new(GlobalName::from("*@optarg")),
-        false,
-        None,
-    )));
-    let stderr_alloc = Arc::new(Alloc::Global(GlobalAlloc::new(
-        Arc::new(GlobalName::from("*@stderr")),
-        false,
-        None,
-    )));
-    let stdin_alloc = Arc::new(Alloc::Global(GlobalAlloc::new(
-        Arc::new(GlobalName::from("*@stdin")),
-        false,
-        None,
-    )));
-    let stdout_alloc = Arc::new(Alloc::Global(GlobalAlloc::new(
-        Arc::new(GlobalName::from("*@stdout")),
-        false,
-        None,
-    )));
-
-    // This one is used by a signature:
-    let ctype_b_loc_alloc = Arc::new(Alloc::Global(GlobalAlloc::new(
-        Arc::new(GlobalName::from("__ctype_b_loc_alloc")),
-        false,
-        None,
-    )));
-    let ctype_b_loc_alloc_alloc = Arc::new(Alloc::Global(GlobalAlloc::new(
-        Arc::new(GlobalName::from("__ctype_b_loc_alloc_alloc")),
-        false,
-        None,
-    )));
-
     let null_alloc = Arc::new(Alloc::Null);
     let top = Arc::new(Alloc::Top);
+    // What a call to a function with unknown effects returns
+    let unknown_return = |call: &InstructionOperand, callee: &UArc<FunctionName>| {
+        if opts.opaque_externs {
+            let opaque = OpaqueAlloc::new(call.instruction_name(), callee.clone());
+            Arc::new(Alloc::Opaque(opaque))
+        } else {
+            top.clone()
+        }
+    };
+    let no_args = Arc::new(Vec::new());
+    let widener = opts.widening.clone().map(|w| Widener::new(w, top.clone()));
+    let widen_operand =
+        |ctx: &Arc<Context>, op, ty: &llvm_ir::TypeRef, a: Arc<Alloc>| match &widener {
+            Some(w) => w.operand(ctx, op, ty, a),
+            None => vec![(a.clone(), a)],
+        };
+    let widen_alloc =
+        |pointer_alloc: &Arc<Alloc>, ty: &llvm_ir::TypeRef, a: Arc<Alloc>| match &widener {
+            Some(w) => w.alloc(pointer_alloc, ty, a),
+            None => vec![(a.clone(), a)],
+        };
+    let parameter_allocs = if opts.library {
+        summary::parameter_allocs(module)
+    } else {
+        HashMap::new()
+    };
+
+    // Context depth of a function
+    let depth = |f: &UArc<FunctionName>| -> usize {
+        opts.function_contexts
+            .get(f)
+            .copied()
+            .unwrap_or(opts.contexts)
+            .into()
+    };
+    let main_ctx = |f: &UArc<FunctionName>| Arc::new(KLimited::new(depth(f), vec![]));
+    let callee_context = |caller: &Context,
+                          call: &InstructionOperand,
+                          callee: &UArc<FunctionName>,
+                          receiver: Option<&Arc<Alloc>>| {
+        let k = depth(callee);
+        let call = call.instruction_name();
+        Arc::new(if k == caller.limit() {
+            policy.callee_context(caller, &call, receiver)
+        } else {
+            policy.callee_context(&caller.truncated(k), &call, receiver)
+        })
+    };
+
+    // Context for an allocation made in the given calling context. Only
+    // allocations that may hold pointers are cloned, see the `alloc` module.
+    let empty_heap_ctx = Arc::new(KLimited::new(0, vec![]));
+    let heap_context = |ctx: &Context, clone: bool| {
+        if clone && opts.heap_contexts > 0 {
+            Arc::new(ctx.truncated(opts.heap_contexts.into()))
+        } else {
+            empty_heap_ctx.clone()
+        }
+    };
+    let pointer_size = module.layout().pointer_size();
+
+    // Whether the callee context of a call depends on the receiver
+    let receiver_sensitive = |call: &InstructionOperand, f: &UArc<FunctionName>| {
+        policy.uses_receiver() && module.has_receiver(f) && !no_receiver.contains(call)
+    };
 
-    let main_ctx = Arc::new(KLimited::new(opts.contexts.into(), vec![]));
+    // Sub-objects at or beyond this offset are not distinguished from the
+    // rest of their allocation. Without a bound, casts and field selections
+    // in a loop could create unboundedly many sub-objects.
+    let field_limit = if opts.field_sensitive {
+        module.max_object_size()
+    } else {
+        0
+    };
 
     // Preprocess the signatures, specializing to the ones for declarations in
     // this module.
@@ -193,44 +296,69 @@ pub fn analysis<'module>(
         }
     }
 
-    let known_functions = HashSet::from([
-        // Used in tests:
-        FunctionName::from("assert_disjoint"),
-        FunctionName::from("assert_may_alias"),
-        FunctionName::from("assert_points_to_nothing"),
-        FunctionName::from("assert_points_to_something"),
-        FunctionName::from("assert_reachable"),
-        FunctionName::from("assert_unreachable"),
-        // Standard:
-        FunctionName::from("__memcpy_chk"),
-        FunctionName::from("calloc"),
-        FunctionName::from("free"),
-        FunctionName::from("realloc"),
-        FunctionName::from("reallocarray"),
-        FunctionName::from("malloc"),
-        FunctionName::from("_Znwm"),
-        // TODO: Signatures for modeling this kind of behavior
-        FunctionName::from("strtol"),
-        FunctionName::from("strtoll"),
-        FunctionName::from("strtoul"),
-        // TODO: Models for these functions
-        // @__cxa_throw
-        // @__cxa_free_exception
-        // @__cxa_begin_catch
-        // @__cxa_allocate_exception
-        // @__cxa_end_catch
-        // @_ZSt9terminatev
-        // @llvm.eh.typeid.for
-        // @_ZNSt11logic_errorC1EPKc
-        // @__gxx_personality_v0
-        // @_ZNSt11logic_errorD1Ev
-    ]);
+    // Globals named by the signatures of declared functions and globals, and
+    // those that point to fresh allocations
+    let mut fresh_globals: HashMap<Arc<GlobalName>, Arc<Alloc>> = HashMap::new();
+    let global_sigs = module
+        .globals
+        .iter()
+        .filter(|(_, g)| g.initializer.is_none())
+        .filter_map(|(g_name, _)| signatures.signatures_for(g_name.get()));
+    let mut signature_globals: HashSet<Arc<GlobalName>> = HashSet::new();
+    for sig in sigs.values().cloned().chain(global_sigs).flatten() {
+        if let Some(global) = sig.global() {
+            signature_globals.insert(Arc::new(GlobalName::from(global)));
+        }
+        if let Signature::GlobalPointsToFresh { global } = sig {
+            let fresh = GlobalAlloc::new(
+                Arc::new(GlobalName::from(format!("*@{global}").as_ref())),
+                false,
+                None,
+            );
+            fresh_globals.insert(
+                Arc::new(GlobalName::from(global.as_ref())),
+                Arc::new(Alloc::Global(fresh)),
+            );
+        }
+    }
+
+    // The allocations of signatures that return the same allocation every
+    // time, keyed by the function and the index of the signature
+    let mut once_allocs: HashMap<(UArc<FunctionName>, usize), Arc<Alloc>> = HashMap::new();
+    for (f, s) in &sigs {
+        let block = UArc::new(BlockName::signature(f.clone()));
+        for (idx, sig) in s.iter().enumerate() {
+            let r#type = match sig {
+                Signature::ReturnAllocOnce { r#type } => r#type,
+                Signature::ArgAllocOnce { .. } => &AllocType::Heap,
+                _ => continue,
+            };
+            // NOTE! See the NOTE in `Instruction::new`. These names are
+            // distinct from those of real instructions, as they're in
+            // declarations rather than definitions.
+            let site = UArc::new(InstructionName::new(f.clone(), block.clone(), idx));
+            let alloc = match r#type {
+                AllocType::Heap => Arc::new(Alloc::Heap(HeapAlloc::new(
+                    site,
+                    empty_heap_ctx.clone(),
+                    None,
+                ))),
+                AllocType::Stack => Arc::new(Alloc::Stack(StackAlloc::signature(
+                    site,
+                    empty_heap_ctx.clone(),
+                ))),
+                AllocType::Top => top.clone(),
+            };
+            once_allocs.insert((f.clone(), idx), alloc);
+        }
+    }
 
     #[allow(clippy::clone_on_copy)]
     #[allow(clippy::redundant_clone)]
     #[allow(clippy::unused_enumerate_index)]
     #[allow(single_use_lifetimes)]
     #[allow(unreachable_code)]
+    #[allow(unused_parens)] // Ascent can't parse unparenthesized or-patterns
     #[allow(unused_variables)]
     let outs = ascent_run! {
         #![measure_rule_times]
@@ -354,56 +482,99 @@ pub fn analysis<'module>(
 
         call(term, op.clone(), Arc::new(args.clone())) <--
           terminator_opcode!(term, opcode),
-          if let TerminatorOpcode::Invoke(Invoke{callee, args}) = &**opcode.as_ref(),
+          if let TerminatorOpcode::Invoke(Invoke{callee, args, ..}) = &**opcode.as_ref(),
           if let Callee::Operand(op) = callee;
 
-        relation calls(
-            Arc<KLimited<UArc<InstructionName>>>,
+        // Like `calls`, but without the callee context
+        relation call_target(
+            Arc<Context>,
             InstructionOperand,
             UArc<FunctionName>,
-            Arc<Vec<Arc<Operand>>>,
-            Arc<KLimited<UArc<InstructionName>>>);
+            Arc<Vec<Arc<Operand>>>);
 
-        calls(
-            ctx,
-            instr,
-            func_alloc.function_name(),
-            args,
-            Arc::new(ctx.pushed(instr.instruction_name()))) <--
-          let span = trace_span!("calls"),
+        call_target(ctx, instr, func_alloc.function_name(), args) <--
+          let span = trace_span!(if cfg!(feature = "relation") {
+              "call_target"
+          } else {
+              "calls"
+          }),
           let _span = span.enter(),
           //
           call(instr, callee_op, args),
           operand_points_to(ctx, callee_op, alloc),
           if let Alloc::Function(func_alloc) = &**alloc,
           //
-          if count("calls", "calls");
+          if count("call_target", "calls");
+
+        // Conservative handling of calls through `Top` (and opaque
+        // allocations, which may be functions unknown code has seen)
+        call_target(ctx, instr, f.clone(), args) <--
+          let span = trace_span!(if cfg!(feature = "relation") {
+              "call_target"
+          } else {
+              "top"
+          }),
+          let _span = span.enter(),
+          //
+          //
+          call(instr, callee_op, args),
+          operand_points_to(ctx, callee_op, alloc),
+          if matches!(&**alloc, Alloc::Top | Alloc::Opaque(_)),
+          for f in indirect_call_targets(module, args.len()),
+          //
+          if count("call_target", "top");
+
+        relation calls(
+            Arc<Context>,
+            InstructionOperand,
+            UArc<FunctionName>,
+            Arc<Vec<Arc<Operand>>>,
+            Arc<Context>);
 
-        // Conservative handling of calls through `Top`
         calls(
             ctx,
             instr,
-            f.clone(),
+            callee,
             args,
-            Arc::new(ctx.pushed(instr.instruction_name()))) <--
+            callee_context(ctx, instr, callee, None)) <--
+          call_target(ctx, instr, callee, args),
+          if !receiver_sensitive(instr, callee),
+          //
+          if count("calls", "calls");
+
+        // The first parameter of the callee in each of its contexts, for
+        // policies that use receivers
+        relation receiver(Arc<Context>, UArc<FunctionName>, Arc<Alloc>);
+
+        calls(ctx, instr, callee, args, callee_ctx.clone()),
+        receiver(callee_ctx.clone(), callee, recv) <--
           let span = trace_span!(if cfg!(feature = "relation") {
               "calls"
           } else {
-              "top"
+              "receiver"
           }),
           let _span = span.enter(),
           //
+          call_target(ctx, instr, callee, args),
+          if receiver_sensitive(instr, callee),
+          if let Some(recv_op) = args.first(),
+          operand_points_to(ctx, recv_op, recv),
+          let callee_ctx = callee_context(ctx, instr, callee, Some(recv)),
           //
-          call(instr, callee_op, args),
-          // TODO: Does this capture `top` if I just use it as a variable?
-          operand_points_to(ctx, callee_op, top.clone()),
-          for f in indirect_call_targets(module, args.len()),
-          //
-          if count("calls", "top");
+          if count("calls", "receiver");
 
-        relation reachable(Arc<KLimited<UArc<InstructionName>>>, UArc<FunctionName>);
+        relation reachable(Arc<Context>, UArc<FunctionName>);
 
-        reachable(main_ctx.clone(), func) <-- main(func);
+        relation entry(UArc<FunctionName>);
+
+        entry(func) <-- main(func);
+
+        entry(func.clone()) <--
+          if opts.library,
+          for (func, f) in &module.functions,
+          if f.exported;
+
+        reachable(main_ctx(func), func) <-- entry(func);
 
         reachable(callee_ctx, callee) <--
           let span = trace_span!("reachable"),
@@ -415,6 +586,17 @@ pub fn analysis<'module>(
           //
           if count("reachable", "reachable");
 
+        // `invoke`
+        reachable(callee_ctx, callee) <--
+          let span = trace_span!("reachable"),
+          let _span = span.enter(),
+          //
+          reachable(caller_ctx, caller),
+          function_terminator!(caller, term),
+          calls(caller_ctx, term, callee, _, callee_ctx),
+          //
+          if count("reachable", "reachable_invoke");
+
         // ----------------------------------------------------------
         // Allocations
         // ----------------------------------------------------------
@@ -423,41 +605,24 @@ pub fn analysis<'module>(
 
         global_alloc(
             g_name,
-            GlobalAlloc::new(g_name.clone(), g.is_const, g.size())) <--
+            GlobalAlloc::new(g_name.clone(), g.is_const, g.size(module.layout()))) <--
           for (g_name, g) in &module.globals;
 
-        // heap
-        operand_points_to(
-            ctx,
-            i.operand(),
-            Arc::new(Alloc::Heap(HeapAlloc::new(i.instruction_name(), sz)))) <--
-          calls(ctx, i, f, args, _),
-          for (name, size_arg) in [
-              ("_Znwm", Some(0)),
-              ("calloc", None),
-              ("malloc", Some(0)),
-              ("realloc", Some(1)),
-              ("reallocarray", None),
-          ],
-          if **f == name,
-          let sz = size_arg.and_then(
-              |i| args.get(i).and_then(|op| op.constant_int())
-          );
-
         // stack
         operand_points_to(
             ctx,
             i.operand(),
-            Arc::new(Alloc::Stack(StackAlloc::alloca(i.instruction_name(), a)))) <--
+            Arc::new(Alloc::Stack(StackAlloc::alloca(i.instruction_name(), heap_ctx, a)))) <--
           reachable_instruction_opcode!(ctx, i, opcode),
-          if let Opcode::Alloca(a) = &&**opcode.as_ref();
+          if let Opcode::Alloca(a) = &&**opcode.as_ref(),
+          let heap_ctx = heap_context(ctx, a.may_hold_pointer);
 
         // ----------------------------------------------------------
         // Points-to
         // ----------------------------------------------------------
 
         relation operand_points_to(
-            Arc<KLimited<UArc<InstructionName>>>,
+            Arc<Context>,
             Arc<Operand>,
             Arc<Alloc>);
 
@@ -496,6 +661,20 @@ pub fn analysis<'module>(
             Arc::new(Alloc::Global(a.clone()))) <--
           global_alloc(g, a);
 
+        // Global aliases point to whatever their aliasees point to. Aliasees
+        // may be other aliases, or constant expressions (e.g., `bitcast`s or
+        // `getelementptr`s) of other globals.
+
+        constant_points_to(Arc::new(Constant::Global(g.clone())), a) <--
+          for (g, aliasee) in &module.aliases,
+          constant_points_to(aliasee, a);
+
+        constant_points_to(aliasee.clone(), Alloc::lookup(a)) <--
+          if !opts.field_sensitive,
+          for aliasee in module.aliases.values(),
+          for c in aliasee.pointers(),
+          constant_points_to(Arc::new(c), a);
+
         // Constant operations (e.g., bitcast, ptrtoint, getelementptr) pass
         // through the points-to facts from their operands.
 
@@ -507,6 +686,7 @@ pub fn analysis<'module>(
           }),
           let _span = span.enter(),
           //
+          if !opts.field_sensitive,
           instruction_opcode!(instr, opcode),
           for op in opcode.as_ref().operands(),
           if let Operand::Constant(c0) = &*op,
@@ -523,6 +703,7 @@ pub fn analysis<'module>(
           }),
           let _span = span.enter(),
           //
+          if !opts.field_sensitive,
           for g in module.globals.values(),
           if let Some(init) = &g.initializer,
           for c in init.pointers(),
@@ -530,6 +711,62 @@ pub fn analysis<'module>(
           //
           if count("constant_points_to", "constant_init");
 
+        // In field-sensitive mode, constant `getelementptr`s select sub-
+        // objects of their operands' allocations.
+
+        relation field_constant(Arc<Constant>);
+
+        field_constant(c.clone()) <--
+          if opts.field_sensitive,
+          instruction_opcode!(instr, opcode),
+          for op in opcode.as_ref().operands(),
+          if let Operand::Constant(c) = &*op;
+
+        field_constant(c.clone()) <--
+          if opts.field_sensitive,
+          terminator_opcode!(instr, opcode),
+          for op in opcode.as_ref().operands(),
+          if let Operand::Constant(c) = &*op;
+
+        field_constant(c.clone()) <--
+          if opts.field_sensitive,
+          for g in module.globals.values(),
+          for (_, c) in g.initializer_offsets(&module.layout);
+
+        field_constant(aliasee.clone()) <--
+          if opts.field_sensitive,
+          for aliasee in module.aliases.values();
+
+        constant_points_to(c0.clone(), Alloc::lookup(&f)) <--
+          let span = trace_span!(if cfg!(feature = "relation") {
+              "constant_points_to"
+          } else {
+              "constant_field"
+          }),
+          let _span = span.enter(),
+          //
+          field_constant(c0),
+          for (c, maybe_offset) in c0.field_pointers(&module.layout),
+          if let Some(offset) = maybe_offset,
+          constant_points_to(Arc::new(c), a),
+          if let Some(f) = Alloc::field(a, offset, field_limit),
+          //
+          if count("constant_points_to", "constant_field");
+
+        relation constant_smear(Arc<Constant>, Arc<Alloc>);
+
+        constant_smear(c0.clone(), Alloc::base(a).clone()) <--
+          field_constant(c0),
+          for (c, offset) in c0.field_pointers(&module.layout),
+          constant_points_to(Arc::new(c), a),
+          if offset.and_then(|o| Alloc::field(a, o, field_limit)).is_none();
+
+        constant_points_to(c, Alloc::lookup(a)) <-- constant_smear(c, a);
+
+        constant_points_to(c, Alloc::lookup(f)) <--
+          constant_smear(c, a),
+          field_of(a, f);
+
         // ----------------------------------------------------------
         // Merging
         // ----------------------------------------------------------
@@ -578,22 +815,44 @@ pub fn analysis<'module>(
         // Stores and loads
         // ----------------------------------------------------------
 
-        relation load(InstructionOperand, Arc<Operand>);
+        // Loads and stores carry the type of the loaded or stored value, see
+        // `Options::widening`.
 
-        load(instr, pointer.clone()) <--
-          instruction_opcode!(instr, opcode),
+        relation load(InstructionOperand, Arc<Operand>, llvm_ir::TypeRef);
+
+        load(instr, pointer.clone(), ty) <--
+          function_instruction_opcode(_, instr, opcode, ty),
           if let Opcode::Load(Load{pointer, ..}) = &**opcode.as_ref();
 
-        relation store(InstructionOperand, Arc<Operand>, Arc<Operand>);
+        load(instr, pointer.clone(), ty) <--
+          function_instruction_opcode(_, instr, opcode, ty),
+          if let Opcode::AtomicRMW(AtomicRMW{pointer, ..}) = &**opcode.as_ref();
+
+        load(instr, pointer.clone(), ty.clone()) <--
+          instruction_opcode!(instr, opcode),
+          if let Opcode::CmpXchg(CmpXchg{pointer, ty, ..}) = &**opcode.as_ref();
+
+        relation store(InstructionOperand, Arc<Operand>, Arc<Operand>, llvm_ir::TypeRef);
+
+        store(instr, pointer.clone(), value.clone(), ty.clone()) <--
+          instruction_opcode!(instr, opcode),
+          if let Opcode::Store(Store{pointer, value, ty}) = &**opcode.as_ref();
+
+        // Any `atomicrmw` operation could produce a pointer derived from the
+        // operand (e.g., `add`), so they're all treated like `xchg`.
+        store(instr, pointer.clone(), value.clone(), ty) <--
+          function_instruction_opcode(_, instr, opcode, ty),
+          if let Opcode::AtomicRMW(AtomicRMW{pointer, value}) = &**opcode.as_ref();
 
-        store(instr, pointer.clone(), value.clone()) <--
+        store(instr, pointer.clone(), replacement.clone(), ty.clone()) <--
           instruction_opcode!(instr, opcode),
-          if let Opcode::Store(Store{pointer, value, ..}) = &**opcode.as_ref();
+          if let Opcode::CmpXchg(CmpXchg{pointer, replacement, ty, ..}) = &**opcode.as_ref();
 
         relation alloc_points_to(Arc<Alloc>, Arc<Alloc>);
 
         // Store instructions
-        alloc_points_to(pointer_alloc, Alloc::lookup(pointee_alloc)) <--
+        alloc_points_to(pointer_alloc, Alloc::lookup(to)),
+        widened(Alloc::lookup(to), Alloc::lookup(member)) <--
           let span = trace_span!(if cfg!(feature = "relation") {
               "alloc_points_to"
           } else {
@@ -601,15 +860,17 @@ pub fn analysis<'module>(
           }),
           let _span = span.enter(),
           //
-          store(instr, pointer, value),
+          store(instr, pointer, value, ty),
           operand_points_to(ctx, value, pointee_alloc),
           operand_points_to(ctx, pointer, pointer_alloc),
           if pointer_alloc.storable(),
+          for (to, member) in widen_alloc(pointer_alloc, ty, pointee_alloc.clone()).iter(),
           //
           if count("alloc_points_to", "store");
 
         // Load instructions
-        operand_points_to(ctx, instr.operand(), Alloc::lookup(pointee_alloc)) <--
+        operand_points_to(ctx, instr.operand(), Alloc::lookup(to)),
+        widened(Alloc::lookup(to), Alloc::lookup(member)) <--
           let span = trace_span!(if cfg!(feature = "relation") {
               "operand_points_to"
           } else {
@@ -617,12 +878,42 @@ pub fn analysis<'module>(
           }),
           let _span = span.enter(),
           //
-          load(instr, pointer),
+          load(instr, pointer, ty),
           operand_points_to(ctx, pointer, pointer_alloc),
           alloc_points_to(pointer_alloc, pointee_alloc),
+          for (to, member) in widen_operand(ctx, instr.operand(), ty, pointee_alloc.clone()).iter(),
           //
           if count("operand_points_to", "load");
 
+        // A stand-in for widened allocations (see `Options::widening`)
+        // aliases its members: each flows its contents into the other, so
+        // loads through pointers to one see stores through pointers to the
+        // other. The loads and stores above pair each allocation they don't
+        // widen with itself.
+        relation widened(Arc<Alloc>, Arc<Alloc>);
+
+        alloc_points_to(stand_in, a) <--
+          widened(stand_in, member),
+          if stand_in != member,
+          alloc_points_to(member, a);
+
+        alloc_points_to(member, a) <--
+          widened(stand_in, member),
+          if stand_in != member && member.storable(),
+          alloc_points_to(stand_in, a);
+
+        // The fields of members are members of the corresponding fields of
+        // the stand-in.
+        widened(Alloc::lookup(&stand_in_field), field.clone()) <--
+          widened(stand_in, member),
+          if stand_in != member,
+          let base = Alloc::base(member),
+          field_of(base, field),
+          if field.offset() > member.offset(),
+          let offset = field.offset() - member.offset(),
+          let stand_in_field = Alloc::field(stand_in, offset, field_limit)
+              .unwrap_or_else(|| stand_in.clone());
+
         // ----------------------------------------------------------
         // Globals
         // ----------------------------------------------------------
@@ -635,6 +926,7 @@ pub fn analysis<'module>(
           }),
           let _span = span.enter(),
           //
+          if !opts.field_sensitive,
           for (g_name, g) in &module.globals,
           if let Some(init) = &g.initializer,
           let init_operand = Arc::new(Operand::Constant(init.clone())),
@@ -643,6 +935,24 @@ pub fn analysis<'module>(
           //
           if count("alloc_points_to", "global_init");
 
+        alloc_points_to(f.clone(), Alloc::lookup(a)) <--
+          let span = trace_span!(if cfg!(feature = "relation") {
+              "alloc_points_to"
+          } else {
+              "global_init_field"
+          }),
+          let _span = span.enter(),
+          //
+          if opts.field_sensitive,
+          for (g_name, g) in &module.globals,
+          global_alloc(g_name, g_alloc),
+          let base = Arc::new(Alloc::Global(g_alloc.clone())),
+          for (offset, c) in g.initializer_offsets(&module.layout),
+          let f = Alloc::field(&base, offset, field_limit).unwrap_or_else(|| base.clone()),
+          constant_points_to(c, a),
+          //
+          if count("alloc_points_to", "global_init_field");
+
         // ----------------------------------------------------------
         // Pass-thru instructions
         // ----------------------------------------------------------
@@ -664,10 +974,11 @@ pub fn analysis<'module>(
           if !matches!(pointer.as_ref(), Operand::Local(_));
 
         pass_thru(i, pointer) <--
+          if !opts.field_sensitive,
           instruction_opcode!(i, opcode),
-          if let Opcode::GetElementPtr(GetElementPtr{pointer, ..}) = &**opcode.as_ref(),
+          if let Opcode::GetElementPtr(GetElementPtr{pointer, zero, ..}) = &**opcode.as_ref(),
           // See NOTE[pass-thru]
-          if !matches!(pointer.as_ref(), Operand::Local(_));
+          if !(*zero && matches!(pointer.as_ref(), Operand::Local(_)));
 
         pass_thru(i, int) <--
           instruction_opcode!(i, opcode),
@@ -698,6 +1009,29 @@ pub fn analysis<'module>(
           instruction_opcode!(i, opcode),
           if let Opcode::Sub(Sub{minuend, ..}) = &**opcode.as_ref();
 
+        // Elements of vectors are not distinguished. `getelementptr`s on
+        // vectors of pointers are handled like other pointer arithmetic.
+
+        pass_thru(i, vector) <--
+          instruction_opcode!(i, opcode),
+          if let Opcode::ExtractElement(ExtractElement{vector}) = &**opcode.as_ref();
+
+        pass_thru(i, vector) <--
+          instruction_opcode!(i, opcode),
+          if let Opcode::InsertElement(InsertElement{vector, ..}) = &**opcode.as_ref();
+
+        pass_thru(i, element) <--
+          instruction_opcode!(i, opcode),
+          if let Opcode::InsertElement(InsertElement{element, ..}) = &**opcode.as_ref();
+
+        pass_thru(i, operand0) <--
+          instruction_opcode!(i, opcode),
+          if let Opcode::ShuffleVector(ShuffleVector{operand0, ..}) = &**opcode.as_ref();
+
+        pass_thru(i, operand1) <--
+          instruction_opcode!(i, opcode),
+          if let Opcode::ShuffleVector(ShuffleVector{operand1, ..}) = &**opcode.as_ref();
+
         operand_points_to(ctx, i.operand(), Alloc::lookup(a)) <--
           let span = trace_span!(if cfg!(feature = "relation") {
               "operand_points_to"
@@ -711,6 +1045,163 @@ pub fn analysis<'module>(
           //
           if count("operand_points_to", "pass_thru");
 
+        // ----------------------------------------------------------
+        // Aggregates
+        // ----------------------------------------------------------
+
+        // Elements of aggregates built up by `insertvalue` are distinguished
+        // by their indices. Other aggregate values (e.g., those returned from
+        // calls, loaded from memory, or produced by a `landingpad`) are not
+        // distinguished from their elements.
+
+        relation insertvalue(Arc<Operand>, Arc<Operand>, Arc<Operand>, Vec<u32>);
+
+        insertvalue(i.operand(), aggregate.clone(), element.clone(), indices.clone()) <--
+          instruction_opcode!(i, opcode),
+          if let Opcode::InsertValue(InsertValue{aggregate, element, indices}) =
+            &**opcode.as_ref();
+
+        // The element of an aggregate at the given indices. The empty path
+        // means the element is unknown.
+        relation element_points_to(Arc<Context>, Arc<Operand>, Vec<u32>, Arc<Alloc>);
+
+        element_points_to(ctx, agg, indices, a) <--
+          insertvalue(agg, _, element, indices),
+          operand_points_to(ctx, element, a);
+
+        // Elements that weren't overwritten
+        element_points_to(ctx, agg, path, a) <--
+          let span = trace_span!(if cfg!(feature = "relation") {
+              "element_points_to"
+          } else {
+              "insertvalue_aggregate"
+          }),
+          let _span = span.enter(),
+          //
+          insertvalue(agg, aggregate, _, indices),
+          element_points_to(ctx, aggregate, path, a),
+          if !path.starts_with(indices),
+          //
+          if count("element_points_to", "insertvalue_aggregate");
+
+        element_points_to(ctx, agg, vec![], a) <--
+          insertvalue(agg, aggregate, _, _),
+          !insertvalue(aggregate, _, _, _),
+          operand_points_to(ctx, aggregate, a);
+
+        operand_points_to(ctx, agg, a) <--
+          element_points_to(ctx, agg, _, a);
+
+        operand_points_to(ctx, i.operand(), a) <--
+          let span = trace_span!(if cfg!(feature = "relation") {
+              "operand_points_to"
+          } else {
+              "extractvalue"
+          }),
+          let _span = span.enter(),
+          //
+          instruction_opcode!(i, opcode),
+          if let Opcode::ExtractValue(ExtractValue{aggregate, indices}) = &**opcode.as_ref(),
+          element_points_to(ctx, aggregate, path, a),
+          if overlapping(path, indices),
+          //
+          if count("operand_points_to", "extractvalue");
+
+        operand_points_to(ctx, i.operand(), a) <--
+          reachable_instruction_opcode!(ctx, i, opcode),
+          if let Opcode::ExtractValue(ExtractValue{aggregate, indices}) = &**opcode.as_ref(),
+          if let Operand::Constant(c) = aggregate.as_ref(),
+          for p in c.element_pointers(indices),
+          constant_points_to(Arc::new(p), a);
+
+        pass_thru(i, aggregate) <--
+          instruction_opcode!(i, opcode),
+          if let Opcode::ExtractValue(ExtractValue{aggregate, ..}) = &**opcode.as_ref(),
+          if !matches!(aggregate.as_ref(), Operand::Constant(_)),
+          !insertvalue(aggregate, _, _, _);
+
+        // ----------------------------------------------------------
+        // Fields
+        // ----------------------------------------------------------
+
+        // In field-sensitive mode, a `getelementptr` that selects a field
+        // (see `GetElementPtr::field_offset`) points to the corresponding
+        // sub-object of each allocation its operand points to. Other pointer
+        // arithmetic may yield a pointer to any part of the allocation, so
+        // the result is "smeared" across the allocation and all of its known
+        // sub-objects.
+
+        relation field_of(Arc<Alloc>, Arc<Alloc>);
+
+        field_of(Alloc::base(a).clone(), a) <--
+          operand_points_to(_, _, a),
+          if let Alloc::Field(_) = &**a;
+
+        field_of(Alloc::base(a).clone(), a) <--
+          alloc_points_to(a, _),
+          if let Alloc::Field(_) = &**a;
+
+        relation gep(InstructionOperand, Arc<Operand>, Option<u64>);
+
+        gep(i, pointer.clone(), *field_offset) <--
+          if opts.field_sensitive,
+          instruction_opcode!(i, opcode),
+          if let Opcode::GetElementPtr(
+              GetElementPtr{pointer, field_offset, zero}
+          ) = &**opcode.as_ref(),
+          // See NOTE[pass-thru]
+          if !(*zero && matches!(pointer.as_ref(), Operand::Local(_)));
+
+        operand_points_to(ctx, i.operand(), Alloc::lookup(&f)) <--
+          let span = trace_span!(if cfg!(feature = "relation") {
+              "operand_points_to"
+          } else {
+              "gep_field"
+          }),
+          let _span = span.enter(),
+          //
+          gep(i, pointer, maybe_offset),
+          if let Some(offset) = maybe_offset,
+          operand_points_to(ctx, pointer, a),
+          if let Some(f) = Alloc::field(a, *offset, field_limit),
+          //
+          if count("operand_points_to", "gep_field");
+
+        relation smear(Arc<Context>, Arc<Operand>, Arc<Alloc>);
+
+        smear(ctx, i.operand(), Alloc::base(a).clone()) <--
+          gep(i, pointer, offset),
+          operand_points_to(ctx, pointer, a),
+          if offset.and_then(|o| Alloc::field(a, o, field_limit)).is_none();
+
+        smear(ctx, i.operand(), Alloc::base(a).clone()) <--
+          if opts.field_sensitive,
+          instruction_opcode!(i, opcode),
+          if let Opcode::Add(Add{operand0, operand1}) = &**opcode.as_ref(),
+          for op in [operand0, operand1],
+          operand_points_to(ctx, op, a);
+
+        smear(ctx, i.operand(), Alloc::base(a).clone()) <--
+          if opts.field_sensitive,
+          instruction_opcode!(i, opcode),
+          if let Opcode::Sub(Sub{minuend, ..}) = &**opcode.as_ref(),
+          operand_points_to(ctx, minuend, a);
+
+        operand_points_to(ctx, op, Alloc::lookup(a)) <-- smear(ctx, op, a);
+
+        operand_points_to(ctx, op, Alloc::lookup(f)) <--
+          let span = trace_span!(if cfg!(feature = "relation") {
+              "operand_points_to"
+          } else {
+              "smear"
+          }),
+          let _span = span.enter(),
+          //
+          smear(ctx, op, a),
+          field_of(a, f),
+          //
+          if count("operand_points_to", "smear");
+
         // ----------------------------------------------------------
         // Function calls
         // ----------------------------------------------------------
@@ -726,11 +1217,20 @@ pub fn analysis<'module>(
           calls(caller_ctx, call_name, callee_name, args, callee_ctx),
           if let Some(callee) = module.functions.get(callee_name),
           for (i, param) in callee.parameters.iter().enumerate(),
+          if i != 0 || !receiver_sensitive(call_name, callee_name),
           if let Some(arg) = args.get(i),
           operand_points_to(caller_ctx, arg, a),
           //
           if count("operand_points_to", "call_arg");
 
+        // Receivers only flow into the contexts they determine
+        operand_points_to(callee_ctx, param, Alloc::lookup(recv)) <--
+          receiver(callee_ctx, callee_name, recv),
+          if let Some(callee) = module.functions.get(callee_name),
+          if let Some(param) = callee.parameters.first(),
+          //
+          if count("operand_points_to", "call_receiver");
+
         // The below operand_points_to rule for returns is actually a hot
         // spot for the analysis. Therefore, we split up the work with this
         // relation.
@@ -756,46 +1256,205 @@ pub fn analysis<'module>(
           if count("operand_points_to", "call_ret");
 
         // ----------------------------------------------------------
-        // memcpy
+        // Variable-arity functions
         // ----------------------------------------------------------
 
-        relation memcpy(
-            Arc<KLimited<UArc<InstructionName>>>,
-            Arc<Operand>,
-            Arc<Operand>,
-            Option<u64>);
+        // Arguments beyond the declared parameters are stored into a
+        // "varargs" allocation, which `llvm.va_start` stores into the
+        // `va_list`. Reading an argument, whether by `va_arg` or by the
+        // target-specific loads that usually replace it, then reads from the
+        // varargs allocation.
 
-        memcpy(ctx, dst, src, sz) <--
-          let span = trace_span!("memcpy"),
-          let _span = span.enter(),
+        relation va_start(Arc<Context>, InstructionOperand, Arc<Operand>, Arc<Alloc>);
+
+        va_start(ctx, i, ap.clone(), varargs_alloc) <--
           calls(ctx, i, f, args, _),
-          if **f == "memcpy" ||
-             **f == "__memcpy_chk" ||
-             f.starts_with("llvm.memcpy") ||
-             f.starts_with("llvm.memmove"),
-          if let Some(dst) = args.first(),
-          if let Some(src) = args.get(1),
-          let sz = {
-              if let Some(op_arc) = args.get(2) {
-                (**op_arc).constant_int()
-              } else {
-                  None
-              }
-          },
-          //
-          if count("memcpy", "memcpy");
+          if **f == "llvm.va_start",
+          if let Some(ap) = args.first(),
+          let varargs_alloc = Arc::new(Alloc::Stack(StackAlloc::varargs(
+              i.instruction_name(),
+              heap_context(ctx, true),
+          )));
 
-        relation memcpy_alloc(Arc<Alloc>, Arc<Alloc>);
-        memcpy_alloc(Alloc::lookup(dst_alloc), Alloc::lookup(src_alloc)) <--
+        // The varargs allocations of a function in each of its contexts
+        relation varargs(Arc<Context>, UArc<FunctionName>, Arc<Alloc>);
+
+        varargs(ctx, func, a) <--
+          va_start(ctx, i, _, a),
+          let func = i.instruction_name().parent_function().clone();
+
+        alloc_points_to(varargs_alloc, Alloc::lookup(a)) <--
+          let span = trace_span!(if cfg!(feature = "relation") {
+              "alloc_points_to"
+          } else {
+              "varargs"
+          }),
+          let _span = span.enter(),
+          //
+          calls(caller_ctx, _, callee_name, args, callee_ctx),
+          varargs(callee_ctx, callee_name, varargs_alloc),
+          if let Some(callee) = module.functions.get(callee_name),
+          for arg in args.iter().skip(callee.parameters.len()),
+          operand_points_to(caller_ctx, arg, a),
+          //
+          if count("alloc_points_to", "varargs");
+
+        alloc_points_to(ap_alloc, varargs_alloc) <--
+          va_start(ctx, _, ap, varargs_alloc),
+          operand_points_to(ctx, ap, ap_alloc),
+          if ap_alloc.storable();
+
+        // The layout of `va_list` is target-specific, so in field-sensitive
+        // mode, `llvm.va_start` stores into all of its fields. Similarly, the
+        // arguments are not distinguished by offset.
+        alloc_points_to(f, varargs_alloc) <--
+          if opts.field_sensitive,
+          va_start(ctx, _, ap, varargs_alloc),
+          operand_points_to(ctx, ap, ap_alloc),
+          if ap_alloc.storable(),
+          let base = Alloc::base(ap_alloc).clone(),
+          field_of(base, f);
+
+        alloc_points_to(f, a) <--
+          if opts.field_sensitive,
+          varargs(_, _, varargs_alloc),
+          field_of(varargs_alloc, f),
+          alloc_points_to(varargs_alloc, a);
+
+        operand_points_to(ctx, i.operand(), Alloc::lookup(a)) <--
+          reachable_instruction_opcode!(ctx, i, opcode),
+          if let Opcode::VAArg(VAArg{arg_list}) = &**opcode.as_ref(),
+          operand_points_to(ctx, arg_list, ap_alloc),
+          alloc_points_to(ap_alloc, varargs_alloc),
+          alloc_points_to(varargs_alloc, a);
+
+        // ----------------------------------------------------------
+        // Exceptions
+        // ----------------------------------------------------------
+
+        // Exceptions are modeled by their allocations, i.e., the argument to
+        // `__cxa_throw`. The exception value produced by a `landingpad` is
+        // treated as pointing to the thrown objects, and so is the result of
+        // `__cxa_begin_catch`. Caught exceptions are tracked per function
+        // rather than per landing pad.
+
+        // Exceptions that may be raised by a call-like instruction
+        relation raises(Arc<Context>, InstructionOperand, Arc<Alloc>);
+
+        raises(ctx, i, a) <--
+          calls(ctx, i, f, args, _),
+          if **f == "__cxa_throw",
+          if let Some(exn) = args.first(),
+          operand_points_to(ctx, exn, a);
+
+        raises(ctx, i, a) <--
+          calls(ctx, i, f, _, _),
+          if **f == "__cxa_rethrow",
+          let func = i.instruction_name().parent_function().clone(),
+          caught(ctx, func, a);
+
+        raises(ctx, i, a) <--
+          let span = trace_span!(if cfg!(feature = "relation") {
+              "raises"
+          } else {
+              "raises_callee"
+          }),
+          let _span = span.enter(),
+          //
+          calls(ctx, i, callee, _, callee_ctx),
+          throws(callee_ctx, callee, a),
+          //
+          if count("raises", "raises_callee");
+
+        // External functions may throw anything, unless their signatures say
+        // otherwise. This is only modeled for `invoke`, see the soundness
+        // caveats in the documentation above.
+        raises(ctx, i, top.clone()) <--
+          calls(ctx, i, callee, _, _),
+          if module.decls.contains_key(callee),
+          if !sigs.get(callee).is_some_and(|s| s.contains(&Signature::Nothrow)),
+          terminator_opcode!(i, op),
+          if let TerminatorOpcode::Invoke(_) = &**op.as_ref();
+
+        // Exceptions that may propagate out of a function
+        relation throws(Arc<Context>, UArc<FunctionName>, Arc<Alloc>);
+
+        throws(ctx, f, a) <--
+          raises(ctx, i, a),
+          function_instruction!(f, i);
+
+        throws(ctx, f, a) <--
+          raises(ctx, t, a),
+          function_terminator_opcode(f, t, op, _),
+          if let TerminatorOpcode::Invoke(Invoke { unwind: None, .. }) = &**op.as_ref();
+
+        throws(ctx, f, a) <--
+          function_terminator_opcode(f, _, op, _),
+          if let TerminatorOpcode::Resume(_) = &**op.as_ref(),
+          caught(ctx, f, a);
+
+        operand_points_to(ctx, lp.clone(), a) <--
+          raises(ctx, t, a),
+          terminator_opcode!(t, op),
+          if let TerminatorOpcode::Invoke(Invoke { unwind: Some(lp), .. }) = &**op.as_ref();
+
+        // Exceptions that may be caught by some landing pad in a function
+        relation caught(Arc<Context>, UArc<FunctionName>, Arc<Alloc>);
+
+        caught(ctx, f, a) <--
+          function_instruction_opcode(f, i, op, _),
+          if let Opcode::LandingPad = &**op.as_ref(),
+          operand_points_to(ctx, i.operand(), a);
+
+        operand_points_to(ctx, call_name.operand(), a) <--
+          calls(ctx, call_name, callee_name, _, _),
+          if **callee_name == "__cxa_begin_catch" ||
+             **callee_name == "__cxa_get_exception_ptr",
+          let func = call_name.instruction_name().parent_function().clone(),
+          caught(ctx, func, a);
+
+        // ----------------------------------------------------------
+        // memcpy
+        // ----------------------------------------------------------
+
+        relation memcpy(
+            Arc<Context>,
+            Arc<Operand>,
+            Arc<Operand>,
+            Option<u64>);
+
+        memcpy(ctx, dst, src, sz) <--
+          let span = trace_span!("memcpy"),
+          let _span = span.enter(),
+          calls(ctx, i, f, args, _),
+          if **f == "memcpy" ||
+             **f == "__memcpy_chk" ||
+             f.starts_with("llvm.memcpy") ||
+             f.starts_with("llvm.memmove") ||
+             **f == "llvm.va_copy",
+          if let Some(dst) = args.first(),
+          if let Some(src) = args.get(1),
+          let sz = {
+              if let Some(op_arc) = args.get(2) {
+                (**op_arc).constant_int()
+              } else {
+                  None
+              }
+          },
+          //
+          if count("memcpy", "memcpy");
+
+        relation memcpy_alloc(Arc<Alloc>, Arc<Alloc>);
+        memcpy_alloc(Alloc::lookup(dst_alloc), Alloc::lookup(src_alloc)) <--
           let span = trace_span!("memcpy_alloc"),
           let _span = span.enter(),
           //
           memcpy(ctx, dst, src, sz),
           let min_size = sz.unwrap_or(0),
           operand_points_to(ctx, src, src_alloc),
-          if src_alloc.loadable(),
+          if src_alloc.loadable() && src_alloc.big_enough(min_size),
           operand_points_to(ctx, dst, dst_alloc),
-          if dst_alloc.storable(),
+          if dst_alloc.storable() && dst_alloc.big_enough(min_size),
           //
           if count("memcpy_alloc", "memcpy_alloc");
 
@@ -812,58 +1471,50 @@ pub fn analysis<'module>(
           //
           if count("alloc_points_to", "alloc_memcpy");
 
+        // In field-sensitive mode, sub-objects are copied to the same offsets
+        // in the destination.
+        alloc_points_to(f.clone(), a) <--
+          let span = trace_span!(if cfg!(feature = "relation") {
+              "alloc_points_to"
+          } else {
+              "alloc_memcpy_field"
+          }),
+          let _span = span.enter(),
+          //
+          if opts.field_sensitive,
+          memcpy_alloc(dst_alloc, src_alloc),
+          field_of(Alloc::base(src_alloc), src_field),
+          if src_field.offset() > src_alloc.offset(),
+          let offset = src_field.offset() - src_alloc.offset(),
+          let f = Alloc::field(dst_alloc, offset, field_limit)
+              .unwrap_or_else(|| Alloc::base(dst_alloc).clone()),
+          alloc_points_to(src_field, a),
+          //
+          if count("alloc_points_to", "alloc_memcpy_field");
+
         // ----------------------------------------------------------
         // argv
         // ----------------------------------------------------------
 
-        operand_points_to(main_ctx.clone(), argv, argv_alloc.clone()) <--
+        operand_points_to(main_ctx(main_name), argv, argv_alloc.clone()) <--
           main(main_name),
           if let Some(func) = module.functions.get(main_name),
           if let Some(argv) = func.parameters.get(1);
 
-        alloc_points_to(argv_alloc, argv0_alloc) <--
+        alloc_points_to(argv_alloc.clone(), argv0_alloc) <--
           if true;
 
         // ----------------------------------------------------------
-        // stderr, stdin, stdout, __ctype_b_loc
+        // Library entry points
         // ----------------------------------------------------------
 
-        // TODO: Some kind of signature for these
+        operand_points_to(main_ctx(func), param, arg_alloc.clone()) <--
+          for ((func, i), (arg_alloc, _)) in &parameter_allocs,
+          if let Some(f) = module.functions.get(func),
+          let param = f.parameters[*i].clone();
 
-        alloc_points_to(
-            Arc::new(Alloc::Global(g_alloc.clone())),
-            optarg_alloc.clone()) <--
-          for (g_name, g) in &module.globals,
-          if **g_name == GlobalName::from("optarg"),
-          if g.initializer.is_none(),
-          global_alloc(g_name, g_alloc);
-
-        alloc_points_to(
-            Arc::new(Alloc::Global(g_alloc.clone())),
-            stderr_alloc.clone()) <--
-          for (g_name, g) in &module.globals,
-          if **g_name == GlobalName::from("stderr"),
-          if g.initializer.is_none(),
-          global_alloc(g_name, g_alloc);
-
-        alloc_points_to(
-            Arc::new(Alloc::Global(g_alloc.clone())),
-            stdin_alloc.clone()) <--
-          for (g_name, g) in &module.globals,
-          if **g_name == GlobalName::from("stdin"),
-          if g.initializer.is_none(),
-          global_alloc(g_name, g_alloc);
-
-        alloc_points_to(
-            Arc::new(Alloc::Global(g_alloc.clone())),
-            stdout_alloc.clone()) <--
-          for (g_name, g) in &module.globals,
-          if **g_name == GlobalName::from("stdout"),
-          if g.initializer.is_none(),
-          global_alloc(g_name, g_alloc);
-
-
-        alloc_points_to(ctype_b_loc_alloc, ctype_b_loc_alloc_alloc) <-- if true;
+        alloc_points_to(arg_alloc.clone(), contents_alloc.clone()) <--
+          for (arg_alloc, contents_alloc) in parameter_allocs.values();
 
         // ----------------------------------------------------------
         // Signatures
@@ -875,14 +1526,11 @@ pub fn analysis<'module>(
 
         needs_signature(name) <--
           for (name, decl) in &module.decls,
-          if !name.starts_with("llvm.memcpy") &&
-             !name.starts_with("llvm.memmove"),
-          if !known_functions.contains(name.as_ref()),
           if !sigs.contains_key(name),
           if decl.has_pointer();
 
         // Functions without signatures must be treated conservatively
-        operand_points_to(ctx, call_name.operand(), top.clone()) <--
+        operand_points_to(ctx, call_name.operand(), unknown_return(call_name, callee_name)) <--
           let span = trace_span!(if cfg!(feature = "relation") {
               "operand_points_to"
           } else {
@@ -897,6 +1545,158 @@ pub fn analysis<'module>(
           //
           if count("operand_points_to", "extern_top");
 
+        // Functions with unknown effects, and whether those include stores and
+        // calls, respectively
+        relation unknown_effects(UArc<FunctionName>, bool, bool);
+
+        unknown_effects(name.clone(), *stores, *calls) <--
+          for (name, s) in &sigs,
+          for sig in s,
+          if let Signature::Unknown { stores, calls } = sig;
+
+        unknown_effects(name, true, true) <--
+          if opts.sound_externs,
+          needs_signature(name);
+
+        operand_points_to(ctx, call_name.operand(), unknown_return(call_name, callee_name)) <--
+          let span = trace_span!(if cfg!(feature = "relation") {
+              "operand_points_to"
+          } else {
+              "unknown_return"
+          }),
+          let _span = span.enter(),
+          //
+          calls(ctx, call_name, callee_name, _, _),
+          unknown_effects(callee_name, _, _),
+          if let Some(decl) = module.decls.get(callee_name),
+          if let llvm_ir::Type::PointerType{ .. } = &*decl.return_type,
+          //
+          if count("operand_points_to", "unknown_return");
+
+        // Allocations reachable from the arguments of calls to functions with
+        // unknown effects, and whether the callee may store to and call them
+        relation unknown_arg_alloc(Arc<Context>, InstructionOperand, bool, bool, Arc<Alloc>);
+        relation unknown_reaches(Arc<Context>, InstructionOperand, bool, bool, Arc<Alloc>);
+
+        unknown_arg_alloc(ctx, call_name, *stores, *calls, a),
+        reachable_alloc_root(a) <--
+          calls(ctx, call_name, callee_name, args, _),
+          unknown_effects(callee_name, stores, calls),
+          for arg in args.iter(),
+          operand_points_to(ctx, arg, a);
+
+        unknown_reaches(ctx, call_name, stores, calls, a) <--
+          unknown_arg_alloc(ctx, call_name, stores, calls, a);
+
+        unknown_reaches(ctx, call_name, stores, calls, b) <--
+          let span = trace_span!("unknown_reaches"),
+          let _span = span.enter(),
+          //
+          unknown_arg_alloc(ctx, call_name, stores, calls, a),
+          reachable_alloc(a, b),
+          //
+          if count("unknown_reaches", "unknown_reaches");
+
+        alloc_points_to(a, top.clone()) <--
+          let span = trace_span!(if cfg!(feature = "relation") {
+              "alloc_points_to"
+          } else {
+              "unknown_store"
+          }),
+          let _span = span.enter(),
+          //
+          unknown_reaches(_, _, stores, _, a),
+          if *stores && a.storable(),
+          //
+          if count("alloc_points_to", "unknown_store");
+
+        alloc_points_to(f, top.clone()) <--
+          let span = trace_span!(if cfg!(feature = "relation") {
+              "alloc_points_to"
+          } else {
+              "unknown_store_field"
+          }),
+          let _span = span.enter(),
+          //
+          unknown_reaches(_, _, stores, _, a),
+          if *stores,
+          field_of(Alloc::base(a), f),
+          if f.storable(),
+          //
+          if count("alloc_points_to", "unknown_store_field");
+
+        // Functions passed to a function with unknown effects may be called,
+        // with unknown arguments
+        callback(ctx, call_name, f.clone(), no_args.clone(), callee_context(ctx, call_name, &f, None)) <--
+          let span = trace_span!(if cfg!(feature = "relation") {
+              "callback"
+          } else {
+              "unknown_call"
+          }),
+          let _span = span.enter(),
+          //
+          unknown_reaches(ctx, call_name, _, calls, a),
+          if *calls,
+          if let Alloc::Function(func_alloc) = &**a,
+          let f = func_alloc.function_name(),
+          //
+          if count("callback", "unknown_call");
+
+        operand_points_to(callee_ctx, param.clone(), top.clone()) <--
+          let span = trace_span!(if cfg!(feature = "relation") {
+              "operand_points_to"
+          } else {
+              "unknown_call_param"
+          }),
+          let _span = span.enter(),
+          //
+          unknown_reaches(ctx, call_name, _, calls, a),
+          if *calls,
+          if let Alloc::Function(func_alloc) = &**a,
+          let callee_name = func_alloc.function_name(),
+          callback(ctx, call_name, callee_name, _, callee_ctx),
+          if let Some(callee) = module.functions.get(&callee_name),
+          for (param, ty) in callee.parameters.iter().zip(&callee.parameter_types),
+          if let llvm_ir::Type::PointerType{ .. } = **ty,
+          //
+          if count("operand_points_to", "unknown_call_param");
+
+        // Opaque allocations (see `Options::opaque_externs`) hold unknown
+        // pointers, which are only known to be into themselves until they
+        // escape to other unknown code. From then on, they're part of `Top`.
+        relation opaque_alloc(Arc<Alloc>);
+        relation opaque_escaped(Arc<Alloc>);
+
+        opaque_alloc(a) <--
+          if opts.opaque_externs,
+          operand_points_to(_, _, a),
+          if let Alloc::Opaque(_) = &**a;
+
+        alloc_points_to(a, a) <-- opaque_alloc(a);
+
+        opaque_escaped(a) <--
+          unknown_reaches(_, _, _, _, a),
+          if let Alloc::Opaque(_) = &**a;
+
+        opaque_escaped(a) <--
+          opaque_alloc(a),
+          alloc_points_to(top.clone(), a);
+
+        opaque_escaped(b) <--
+          opaque_escaped(a),
+          alloc_points_to(a, b),
+          if let Alloc::Opaque(_) = &**b;
+
+        alloc_points_to(a, top.clone()) <-- opaque_escaped(a);
+
+        operand_points_to(ctx, op, top.clone()) <--
+          opaque_escaped(a),
+          operand_points_to(ctx, op, a);
+
+        alloc_points_to(b, top.clone()) <--
+          opaque_escaped(a),
+          alloc_points_to(b, a);
+
         operand_points_to(ctx, call_name.operand(), a) <--
           let span = trace_span!(if cfg!(feature = "relation") {
               "operand_points_to"
@@ -905,16 +1705,23 @@ pub fn analysis<'module>(
           }),
           let _span = span.enter(),
           //
-          calls(ctx, call_name, callee_name, _, _),
+          calls(ctx, call_name, callee_name, args, _),
           if let Some(s) = sigs.get(callee_name),
           for sig in s,
-          if let Signature::ReturnAlloc { r#type } = sig,
+          if let Signature::ReturnAlloc { r#type, size_arg, count_arg } = sig,
           let a = match r#type {
-              AllocType::Heap => Arc::new(Alloc::Heap(
-                  HeapAlloc::new(call_name.instruction_name(), None))
-              ),
+              AllocType::Heap => {
+                  let arg_int = |i: &usize| args.get(*i).and_then(|op| op.constant_int());
+                  let size = size_arg.and_then(|i| arg_int(&i));
+                  let sz = match count_arg {
+                      None => size,
+                      Some(i) => size.zip(arg_int(i)).and_then(|(s, c)| s.checked_mul(c)),
+                  };
+                  let heap_ctx = heap_context(ctx, sz.is_none_or(|s| s >= pointer_size));
+                  Arc::new(Alloc::Heap(HeapAlloc::new(call_name.instruction_name(), heap_ctx, sz)))
+              }
               AllocType::Stack => Arc::new(Alloc::Stack(
-                  StackAlloc::signature(call_name.instruction_name()))
+                  StackAlloc::signature(call_name.instruction_name(), heap_context(ctx, true)))
               ),
               AllocType::Top => top.clone(),
           },
@@ -932,12 +1739,120 @@ pub fn analysis<'module>(
           calls(ctx, call_name, callee_name, args, _),
           if let Some(s) = sigs.get(callee_name),
           for sig in s,
-          if let Signature::ReturnAliasesArg { arg } = sig,
+          if let (Signature::ReturnAliasesArg { arg }
+            | Signature::ReturnAliasesArgReachable { arg }) = sig,
           if let Some(op) = args.get(*arg),
           operand_points_to(ctx, op, a),
           //
           if count("operand_points_to", "sig_return_aliases_arg");
 
+        // Globals named by signatures, which might not be declared in this
+        // module
+        relation signature_global(Arc<GlobalName>, Arc<GlobalAlloc>);
+
+        signature_global(g_name, alloc_name) <--
+          for g_name in &signature_globals,
+          global_alloc(g_name, alloc_name);
+
+        signature_global(g_name.clone(), alloc_name) <--
+          for g_name in &signature_globals,
+          !global_alloc(g_name, _),
+          let alloc_name = GlobalAlloc::new(g_name.clone(), false, None);
+
+        alloc_points_to(Arc::new(Alloc::Global(g_alloc.clone())), fresh.clone()) <--
+          for (g_name, fresh) in &fresh_globals,
+          if module.globals.get(g_name).is_none_or(|g| g.initializer.is_none()),
+          signature_global(g_name, g_alloc);
+
+        // Allocations reachable from the object containing the first one by
+        // following pointers, for the `-reachable` signatures. Only computed
+        // for the roots that those signatures need.
+        relation reachable_alloc_root(Arc<Alloc>);
+        relation reachable_alloc(Arc<Alloc>, Arc<Alloc>);
+
+        reachable_alloc(root, a) <--
+          reachable_alloc_root(root),
+          alloc_points_to(Alloc::base(root), a);
+
+        reachable_alloc(root, a) <--
+          reachable_alloc_root(root),
+          field_of(Alloc::base(root), f),
+          alloc_points_to(f, a);
+
+        reachable_alloc(root, b) <--
+          let span = trace_span!("reachable_alloc"),
+          let _span = span.enter(),
+          //
+          reachable_alloc(root, a),
+          alloc_points_to(Alloc::base(a), b),
+          //
+          if count("reachable_alloc", "reachable_alloc");
+
+        reachable_alloc(root, b) <--
+          let span = trace_span!(if cfg!(feature = "relation") {
+              "reachable_alloc"
+          } else {
+              "reachable_alloc_field"
+          }),
+          let _span = span.enter(),
+          //
+          reachable_alloc(root, a),
+          field_of(Alloc::base(a), f),
+          alloc_points_to(f, b),
+          //
+          if count("reachable_alloc", "reachable_alloc_field");
+
+        reachable_alloc_root(Arc::new(Alloc::Global(alloc_name.clone()))) <--
+          for s in sigs.values(),
+          for sig in s,
+          if let (Signature::ReturnAliasesGlobalReachable { global }
+            | Signature::ArgMemcpyGlobalReachable { global, .. }) = sig,
+          let global_name = Arc::new(GlobalName::from(global.as_ref())),
+          signature_global(global_name, alloc_name);
+
+        reachable_alloc_root(a) <--
+          calls(ctx, _, callee_name, args, _),
+          if let Some(s) = sigs.get(callee_name),
+          for sig in s,
+          if let (Signature::ReturnAliasesArgReachable { arg: src }
+            | Signature::ArgMemcpyArgReachable { src, .. }) = sig,
+          if let Some(op) = args.get(*src),
+          operand_points_to(ctx, op, a);
+
+        operand_points_to(ctx, call_name.operand(), a.clone()) <--
+          let span = trace_span!(if cfg!(feature = "relation") {
+              "operand_points_to"
+          } else {
+              "sig_return_alloc_once"
+          }),
+          let _span = span.enter(),
+          //
+          calls(ctx, call_name, callee_name, _, _),
+          if let Some(s) = sigs.get(callee_name),
+          for (idx, sig) in s.iter().enumerate(),
+          if let Signature::ReturnAllocOnce { .. } = sig,
+          if let Some(a) = once_allocs.get(&(callee_name.clone(), idx)),
+          //
+          if count("operand_points_to", "sig_return_alloc_once");
+
+        operand_points_to(ctx, call_name.operand(), Alloc::lookup(b)) <--
+          let span = trace_span!(if cfg!(feature = "relation") {
+              "operand_points_to"
+          } else {
+              "sig_return_aliases_arg_reachable"
+          }),
+          let _span = span.enter(),
+          //
+          calls(ctx, call_name, callee_name, args, _),
+          if let Some(s) = sigs.get(callee_name),
+          for sig in s,
+          if let Signature::ReturnAliasesArgReachable { arg } = sig,
+          if let Some(op) = args.get(*arg),
+          operand_points_to(ctx, op, a),
+          reachable_alloc(a, b),
+          //
+          if count("operand_points_to", "sig_return_aliases_arg_reachable");
+
         operand_points_to(
             ctx,
             call_name.operand(),
@@ -954,30 +1869,106 @@ pub fn analysis<'module>(
           for sig in s,
           if let Signature::ReturnPointsToGlobal { global } = sig,
           let global_name = Arc::new(GlobalName::from(global.as_ref())),
-          global_alloc(global_name, alloc_name),
+          signature_global(global_name, alloc_name),
           //
           if count("operand_points_to", "sig_return_points_to_global");
 
-        operand_points_to(
-            ctx,
-            call_name.operand(),
-            Arc::new(Alloc::Global(alloc_name))) <--
+        operand_points_to(ctx, call_name.operand(), Alloc::lookup(a)) <--
           let span = trace_span!(if cfg!(feature = "relation") {
               "operand_points_to"
           } else {
-              "sig_return_points_to_global_fresh"
+              "sig_return_aliases_global"
           }),
           let _span = span.enter(),
           //
           calls(ctx, call_name, callee_name, _, _),
           if let Some(s) = sigs.get(callee_name),
           for sig in s,
-          if let Signature::ReturnPointsToGlobal { global } = sig,
+          if let (Signature::ReturnAliasesGlobal { global }
+            | Signature::ReturnAliasesGlobalReachable { global }) = sig,
+          let global_name = Arc::new(GlobalName::from(global.as_ref())),
+          signature_global(global_name, alloc_name),
+          alloc_points_to(Arc::new(Alloc::Global(alloc_name.clone())), a),
+          //
+          if count("operand_points_to", "sig_return_aliases_global");
+
+        operand_points_to(ctx, call_name.operand(), Alloc::lookup(a)) <--
+          let span = trace_span!(if cfg!(feature = "relation") {
+              "operand_points_to"
+          } else {
+              "sig_return_aliases_global_reachable"
+          }),
+          let _span = span.enter(),
+          //
+          calls(ctx, call_name, callee_name, _, _),
+          if let Some(s) = sigs.get(callee_name),
+          for sig in s,
+          if let Signature::ReturnAliasesGlobalReachable { global } = sig,
+          let global_name = Arc::new(GlobalName::from(global.as_ref())),
+          signature_global(global_name, alloc_name),
+          reachable_alloc(Arc::new(Alloc::Global(alloc_name.clone())), a),
+          //
+          if count("operand_points_to", "sig_return_aliases_global_reachable");
+
+        alloc_points_to(dst_alloc, a) <--
+          let span = trace_span!(if cfg!(feature = "relation") {
+              "alloc_points_to"
+          } else {
+              "sig_arg_alloc"
+          }),
+          let _span = span.enter(),
+          //
+          calls(ctx, call_name, callee_name, args, _),
+          if let Some(s) = sigs.get(callee_name),
+          for sig in s,
+          if let Signature::ArgAlloc { arg } = sig,
+          if let Some(op) = args.get(*arg),
+          operand_points_to(ctx, op, dst_alloc),
+          if dst_alloc.storable(),
+          let a = Arc::new(Alloc::Heap(
+              HeapAlloc::new(call_name.instruction_name(), heap_context(ctx, true), None)
+          )),
+          //
+          if count("alloc_points_to", "sig_arg_alloc");
+
+        alloc_points_to(dst_alloc, a.clone()) <--
+          let span = trace_span!(if cfg!(feature = "relation") {
+              "alloc_points_to"
+          } else {
+              "sig_arg_alloc_once"
+          }),
+          let _span = span.enter(),
+          //
+          calls(ctx, call_name, callee_name, args, _),
+          if let Some(s) = sigs.get(callee_name),
+          for (idx, sig) in s.iter().enumerate(),
+          if let Signature::ArgAllocOnce { arg } = sig,
+          if let Some(op) = args.get(*arg),
+          operand_points_to(ctx, op, dst_alloc),
+          if dst_alloc.storable(),
+          if let Some(a) = once_allocs.get(&(callee_name.clone(), idx)),
+          //
+          if count("alloc_points_to", "sig_arg_alloc_once");
+
+        alloc_points_to(dst_alloc, Arc::new(Alloc::Global(alloc_name.clone()))) <--
+          let span = trace_span!(if cfg!(feature = "relation") {
+              "alloc_points_to"
+          } else {
+              "sig_arg_points_to_global"
+          }),
+          let _span = span.enter(),
+          //
+          calls(ctx, call_name, callee_name, args, _),
+          if let Some(s) = sigs.get(callee_name),
+          for sig in s,
+          if let Signature::ArgPointsToGlobal { arg, global } = sig,
+          if let Some(op) = args.get(*arg),
+          operand_points_to(ctx, op, dst_alloc),
+          if dst_alloc.storable(),
           let global_name = Arc::new(GlobalName::from(global.as_ref())),
-          !global_alloc(global_name, _),
-          let alloc_name = GlobalAlloc::new(global_name.clone(), false, None),
+          signature_global(global_name, alloc_name),
           //
-          if count("operand_points_to", "sig_return_points_to_global_fresh");
+          if count("alloc_points_to", "sig_arg_points_to_global");
 
         memcpy(ctx, dst_op.clone(), src_op.clone(), None) <--
           let span = trace_span!(if cfg!(feature = "relation") {
@@ -990,13 +1981,155 @@ pub fn analysis<'module>(
           calls(ctx, call_name, callee_name, args, _),
           if let Some(s) = sigs.get(callee_name),
           for sig in s,
-          if let Signature::ArgMemcpyArg { dst, src } = sig,
+          if let (Signature::ArgMemcpyArg { dst, src }
+            | Signature::ArgMemcpyArgReachable { dst, src }) = sig,
           if dst != src,
           if let Some(dst_op) = args.get(*dst),
           if let Some(src_op) = args.get(*src),
           //
           if count("memcpy", "sig_arg_memcpy_arg");
 
+        alloc_points_to(dst_alloc, Alloc::lookup(a)) <--
+          let span = trace_span!(if cfg!(feature = "relation") {
+              "alloc_points_to"
+          } else {
+              "sig_arg_memcpy_arg_reachable"
+          }),
+          let _span = span.enter(),
+          //
+          calls(ctx, call_name, callee_name, args, _),
+          if let Some(s) = sigs.get(callee_name),
+          for sig in s,
+          if let Signature::ArgMemcpyArgReachable { dst, src } = sig,
+          if let Some(dst_op) = args.get(*dst),
+          if let Some(src_op) = args.get(*src),
+          operand_points_to(ctx, src_op, src_alloc),
+          reachable_alloc(src_alloc, a),
+          operand_points_to(ctx, dst_op, dst_alloc),
+          if dst_alloc.storable(),
+          //
+          if count("alloc_points_to", "sig_arg_memcpy_arg_reachable");
+
+        memcpy_alloc(Alloc::lookup(dst_alloc), Arc::new(Alloc::Global(alloc_name.clone()))) <--
+          let span = trace_span!(if cfg!(feature = "relation") {
+              "memcpy_alloc"
+          } else {
+              "sig_arg_memcpy_global"
+          }),
+          let _span = span.enter(),
+          //
+          calls(ctx, call_name, callee_name, args, _),
+          if let Some(s) = sigs.get(callee_name),
+          for sig in s,
+          if let (Signature::ArgMemcpyGlobal { dst, global }
+            | Signature::ArgMemcpyGlobalReachable { dst, global }) = sig,
+          if let Some(dst_op) = args.get(*dst),
+          operand_points_to(ctx, dst_op, dst_alloc),
+          if dst_alloc.storable(),
+          let global_name = Arc::new(GlobalName::from(global.as_ref())),
+          signature_global(global_name, alloc_name),
+          //
+          if count("memcpy_alloc", "sig_arg_memcpy_global");
+
+        alloc_points_to(dst_alloc, Alloc::lookup(a)) <--
+          let span = trace_span!(if cfg!(feature = "relation") {
+              "alloc_points_to"
+          } else {
+              "sig_arg_memcpy_global_reachable"
+          }),
+          let _span = span.enter(),
+          //
+          calls(ctx, call_name, callee_name, args, _),
+          if let Some(s) = sigs.get(callee_name),
+          for sig in s,
+          if let Signature::ArgMemcpyGlobalReachable { dst, global } = sig,
+          if let Some(dst_op) = args.get(*dst),
+          operand_points_to(ctx, dst_op, dst_alloc),
+          if dst_alloc.storable(),
+          let global_name = Arc::new(GlobalName::from(global.as_ref())),
+          signature_global(global_name, alloc_name),
+          reachable_alloc(Arc::new(Alloc::Global(alloc_name.clone())), a),
+          //
+          if count("alloc_points_to", "sig_arg_memcpy_global_reachable");
+
+        // Callbacks are called by the external function rather than by the
+        // call to it, so they get their own relation: their parameters receive
+        // the forwarded arguments, but their return values don't flow to the
+        // call. They're still callgraph edges from the call.
+
+        relation callback(
+            Arc<Context>,
+            InstructionOperand,
+            UArc<FunctionName>,
+            Arc<Vec<Arc<Operand>>>,
+            Arc<Context>);
+
+        callback(ctx, call_name, f.clone(), fwd.clone(), callee_context(ctx, call_name, &f, None)) <--
+          let span = trace_span!(if cfg!(feature = "relation") {
+              "callback"
+          } else {
+              "sig_calls_arg"
+          }),
+          let _span = span.enter(),
+          //
+          calls(ctx, call_name, callee_name, args, _),
+          if let Some(s) = sigs.get(callee_name),
+          for sig in s,
+          if let Signature::CallsArg { arg, args: forwarded } = sig,
+          if let Some(op) = args.get(*arg),
+          // Out-of-range indices are skipped by `Signatures::check_arities`,
+          // unless the callee is variadic
+          let fwd = Arc::new(forwarded.iter().filter_map(|i| args.get(*i).cloned()).collect::<Vec<_>>()),
+          operand_points_to(ctx, op, alloc),
+          if let Alloc::Function(func_alloc) = &**alloc,
+          let f = func_alloc.function_name(),
+          //
+          if count("callback", "sig_calls_arg");
+
+        callback(ctx, call_name, f.clone(), fwd.clone(), callee_context(ctx, call_name, &f, None)) <--
+          let span = trace_span!(if cfg!(feature = "relation") {
+              "callback"
+          } else {
+              "sig_calls_arg_top"
+          }),
+          let _span = span.enter(),
+          //
+          calls(ctx, call_name, callee_name, args, _),
+          if let Some(s) = sigs.get(callee_name),
+          for sig in s,
+          if let Signature::CallsArg { arg, args: forwarded } = sig,
+          if let Some(op) = args.get(*arg),
+          let fwd = Arc::new(forwarded.iter().filter_map(|i| args.get(*i).cloned()).collect::<Vec<_>>()),
+          operand_points_to(ctx, op, alloc),
+          if matches!(&**alloc, Alloc::Top | Alloc::Opaque(_)),
+          for f in indirect_call_targets(module, fwd.len()),
+          //
+          if count("callback", "sig_calls_arg_top");
+
+        reachable(callee_ctx, callee) <--
+          callback(_, _, callee, _, callee_ctx);
+
+        operand_points_to(callee_ctx, param, Alloc::lookup(a)) <--
+          let span = trace_span!(if cfg!(feature = "relation") {
+              "operand_points_to"
+          } else {
+              "callback_arg"
+          }),
+          let _span = span.enter(),
+          //
+          callback(caller_ctx, _, callee_name, args, callee_ctx),
+          if let Some(callee) = module.functions.get(callee_name),
+          for (param, arg) in callee.parameters.iter().zip(args.iter()),
+          operand_points_to(caller_ctx, arg, a),
+          //
+          if count("operand_points_to", "callback_arg");
+
+        // Exceptions thrown by callbacks propagate through the external
+        // function
+        raises(ctx, i, a) <--
+          callback(ctx, i, callee, _, callee_ctx),
+          throws(callee_ctx, callee, a);
+
         // ----------------------------------------------------------
         // Assertions
         // ----------------------------------------------------------
@@ -1053,6 +2186,7 @@ pub fn analysis<'module>(
 
         relation callgraph(UArc<InstructionName>, UArc<FunctionName>);
         callgraph(i.instruction_name(), f) <-- calls(_, i, f, _, _);
+        callgraph(i.instruction_name(), f.clone()) <-- callback(_, i, f, _, _);
 
         relation free_non_heap(Arc<Alloc>);
         free_non_heap(a.clone()) <--
@@ -1072,7 +2206,7 @@ pub fn analysis<'module>(
         relation invalid_load(Arc<Operand>, Arc<Alloc>);
         invalid_load(pointer.clone(), alloc.clone()) <--
           if opts.metrics,
-          load(instr, pointer),
+          load(instr, pointer, _),
           operand_points_to(_ctx, pointer, alloc),
           if !alloc.loadable();
 
@@ -1082,7 +2216,7 @@ pub fn analysis<'module>(
           memcpy(ctx, dst, _, sz),
           let min_size = sz.unwrap_or(0),
           operand_points_to(ctx, dst, dst_alloc),
-          if !dst_alloc.storable();
+          if !dst_alloc.storable() || !dst_alloc.big_enough(min_size);
 
         relation invalid_memcpy_src(Arc<Operand>, Arc<Alloc>);
         invalid_memcpy_src(src.clone(), src_alloc.clone()) <--
@@ -1090,12 +2224,12 @@ pub fn analysis<'module>(
           memcpy(ctx, _, src, sz),
           let min_size = sz.unwrap_or(0),
           operand_points_to(ctx, src, src_alloc),
-          if !src_alloc.loadable();
+          if !src_alloc.loadable() || !src_alloc.big_enough(min_size);
 
         relation invalid_store(Arc<Operand>, Arc<Alloc>);
         invalid_store(pointer.clone(), alloc.clone()) <--
           if opts.metrics,
-          store(instr, pointer, _),
+          store(instr, pointer, _, _),
           operand_points_to(_ctx, pointer, alloc),
           if !alloc.storable();
 
@@ -1103,6 +2237,11 @@ pub fn analysis<'module>(
         points_to_top(op.clone()) <--
           operand_points_to(_, op, alloc),
           if let Alloc::Top = &**alloc;
+
+        relation points_to_opaque(Arc<Operand>);
+        points_to_opaque(op.clone()) <--
+          operand_points_to(_, op, alloc),
+          if let Alloc::Opaque(_) = &**alloc;
     };
 
     if opts.debug {
@@ -1110,18 +2249,73 @@ pub fn analysis<'module>(
         eprintln!("{}", outs.scc_times_summary());
     }
 
-    OutputRelations {
-        alloc_points_to: outs
-            .alloc_points_to
-            .into_iter()
-            .map(|(i, a)| (i, Alloc::lookup(&a)))
-            .collect(),
-        operand_points_to: outs
+    // Calls whose receiver points to nothing (in some context), so that the
+    // rules above create no edge for them
+    let mut empty_receivers = HashSet::new();
+    if policy.uses_receiver() {
+        let nonempty: HashSet<_> = outs
             .operand_points_to
-            .into_iter()
-            .map(|(c, i, a)| (c, i, Alloc::lookup(&a)))
+            .iter()
+            .map(|(ctx, op, _)| (ctx, op))
+            .collect();
+        for (ctx, instr, callee, args) in &outs.call_target {
+            if let Some(recv) = args.first() {
+                if receiver_sensitive(instr, callee) && !nonempty.contains(&(ctx, recv)) {
+                    empty_receivers.insert(instr.clone());
+                }
+            }
+        }
+    }
+
+    // Facts derived before later merges may refer to allocations that are no
+    // longer representatives, so canonicalize (and deduplicate) them
+    let callgraph_size = outs.callgraph.len();
+    let alloc_points_to = outs
+        .alloc_points_to
+        .into_iter()
+        .map(|(a, b)| (Alloc::lookup(&a), Alloc::lookup(&b)))
+        .collect::<HashSet<_>>();
+    let operand_points_to = outs
+        .operand_points_to
+        .into_iter()
+        .map(|(c, i, a)| (c, i, Alloc::lookup(&a)))
+        .collect::<HashSet<_>>();
+
+    // Which allocations were kept in a widened points-to set depends on the
+    // order in which they reached it, so drop members from sets that also
+    // contain their stand-in, which aliases them anyway
+    let mut stand_ins: HashMap<Arc<Alloc>, Vec<Arc<Alloc>>> = HashMap::new();
+    for (stand_in, member) in &outs.widened {
+        if stand_in != member {
+            stand_ins
+                .entry(Alloc::lookup(member))
+                .or_default()
+                .push(Alloc::lookup(stand_in));
+        }
+    }
+    let outputs = OutputRelations {
+        alloc_points_to: alloc_points_to
+            .iter()
+            .filter(|(a, b)| {
+                stand_ins.get(b).is_none_or(|ss| {
+                    !ss.iter()
+                        .any(|s| alloc_points_to.contains(&(a.clone(), s.clone())))
+                })
+            })
+            .cloned()
+            .collect(),
+        operand_points_to: operand_points_to
+            .iter()
+            .filter(|(c, i, a)| {
+                stand_ins.get(a).is_none_or(|ss| {
+                    !ss.iter()
+                        .any(|s| operand_points_to.contains(&(c.clone(), i.clone(), s.clone())))
+                })
+            })
+            .cloned()
             .collect(),
         reachable: outs.reachable.into_iter().map(|tup| tup.1).collect(),
+        callgraph: outs.callgraph,
         calls: outs
             .calls
             .into_iter()
@@ -1130,7 +2324,7 @@ pub fn analysis<'module>(
         needs_signature: outs.needs_signature.into_iter().map(|tup| tup.0).collect(),
         metrics: if opts.metrics {
             Some(Metrics {
-                callgraph_size: outs.callgraph.len(),
+                callgraph_size,
                 free_non_heap: outs.free_non_heap.len(),
                 invalid_calls: outs.invalid_call.len(),
                 invalid_loads: outs.invalid_load.len(),
@@ -1138,9 +2332,12 @@ pub fn analysis<'module>(
                 invalid_memcpy_srcs: outs.invalid_memcpy_dst.len(),
                 invalid_stores: outs.invalid_store.len(),
                 points_to_top: outs.points_to_top.len(),
+                points_to_opaque: outs.points_to_opaque.len(),
+                widened: widener.as_ref().map(Widener::collapsed).unwrap_or(0),
             })
         } else {
             None
         },
-    }
+    };
+    (outputs, empty_receivers)
 }
@@ -0,0 +1,274 @@
+// SPDX-License-Identifier: BSD-3-Clause
+//! Signatures that summarize the exported functions of a library, so that
+//! programs using it can be analyzed without linking it in.
+//!
+//! The library is analyzed with [`Options::library`] set, so that each pointer
+//! parameter `i` of an exported function `f` points to a placeholder
+//! allocation `*f:argi`, which in turn points to a placeholder `*f:argi[*]`.
+//! The signatures of `f` are read off from where its placeholders end up:
+//!
+//! - Returning `*f:argi` or `*f:argi[*]`: `return-aliases-arg` or
+//!   `return-aliases-arg-reachable`
+//! - Returning a heap allocation, a global, or an unknown pointer or a
+//!   function: `return-alloc` (`heap` or `top`) or `return-points-to-global`
+//! - `*f:argi` pointing to `*f:argj[*]`, a heap allocation, or a global:
+//!   `arg-memcpy-arg`, `arg-alloc`, or `arg-points-to-global`
+//! - Calling `*f:argi`: `calls-arg`, forwarding the arguments of the call if
+//!   they're all parameters of `f`
+//! - Effects on placeholders that no signature can express, e.g., `*f:argi`
+//!   pointing to `*f:argj` (as in `void set(struct s *s, char *p) { s->p = p;
+//!   }`), unknown pointers or functions stored into `*f:argi`, or
+//!   placeholders of other functions ending up in those of `f`:
+//!   `unknown` without calls, which conservatively stores `Top` into anything
+//!   reachable from the arguments (and returns `Top`)
+//!
+//! Other effects, e.g., on globals or through pointers stored in the
+//! pointees of parameters, are not captured.
+//!
+//! [`Options::library`]: crate::analysis::pointer::Options::library
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::{
+    alloc::{Alloc, AllocSite, GlobalAlloc},
+    analysis::pointer::OutputRelations,
+    arc::{Arc, UArc},
+    llvm::instruction::{Call, Opcode},
+    llvm::{Callee, FunctionName, GlobalName, Invoke, Module, Operand, TerminatorOpcode},
+    signatures::{AllocType, Signature},
+};
+
+/// Placeholder allocations for the pointer parameters of exported functions:
+/// what each parameter points to, and what that points to
+#[allow(clippy::type_complexity)]
+pub(crate) fn parameter_allocs(
+    module: &Module,
+) -> HashMap<(UArc<FunctionName>, usize), (Arc<Alloc>, Arc<Alloc>)> {
+    let placeholder = |name: String| {
+        Arc::new(Alloc::Global(GlobalAlloc::new(
+            Arc::new(GlobalName::from(name.as_ref())),
+            false,
+            None,
+        )))
+    };
+    let mut allocs = HashMap::new();
+    for (f_name, f) in &module.functions {
+        if !f.exported {
+            continue;
+        }
+        for (i, ty) in f.parameter_types.iter().enumerate() {
+            if let llvm_ir::Type::PointerType { .. } = **ty {
+                let name = f_name.get();
+                allocs.insert(
+                    (f_name.clone(), i),
+                    (
+                        placeholder(format!("*{name}:arg{i}")),
+                        placeholder(format!("*{name}:arg{i}[*]")),
+                    ),
+                );
+            }
+        }
+    }
+    allocs
+}
+
+#[derive(Clone, Copy)]
+enum Placeholder<'a> {
+    /// What parameter `i` of the function points to
+    Arg(&'a UArc<FunctionName>, usize),
+    /// What that points to
+    Contents(&'a UArc<FunctionName>, usize),
+}
+
+fn push(sigs: &mut Vec<Signature>, sig: Signature) {
+    if !sigs.contains(&sig) {
+        sigs.push(sig);
+    }
+}
+
+/// Stands in for effects that no signature expresses more precisely
+fn inexpressible() -> Signature {
+    Signature::Unknown {
+        stores: true,
+        calls: false,
+    }
+}
+
+fn global(a: &Arc<Alloc>) -> Option<String> {
+    match Alloc::site(a) {
+        AllocSite::Global(g) => Some(g.get().to_string()),
+        _ => None,
+    }
+}
+
+/// Signatures for the exported functions of a library, keyed by regexes that
+/// match exactly their names. `outs` must come from an analysis of `module`
+/// with [`crate::analysis::pointer::Options::library`] set.
+pub fn signatures(module: &Module, outs: &OutputRelations) -> BTreeMap<String, Vec<Signature>> {
+    let parameter_allocs = parameter_allocs(module);
+    let mut placeholders: HashMap<&Arc<Alloc>, Placeholder> = HashMap::new();
+    for ((f, i), (arg, contents)) in &parameter_allocs {
+        placeholders.insert(arg, Placeholder::Arg(f, *i));
+        placeholders.insert(contents, Placeholder::Contents(f, *i));
+    }
+
+    let mut operand_points_to: HashMap<&Arc<Operand>, HashSet<&Arc<Alloc>>> = HashMap::new();
+    for (_, op, a) in &outs.operand_points_to {
+        operand_points_to
+            .entry(op)
+            .or_default()
+            .insert(Alloc::base(a));
+    }
+    let mut alloc_points_to: HashMap<&Arc<Alloc>, HashSet<&Arc<Alloc>>> = HashMap::new();
+    for (a, b) in &outs.alloc_points_to {
+        alloc_points_to
+            .entry(Alloc::base(a))
+            .or_default()
+            .insert(Alloc::base(b));
+    }
+    let empty = HashSet::new();
+    let points_to = |op: &Arc<Operand>| operand_points_to.get(op).unwrap_or(&empty);
+
+    let mut sigs: HashMap<&UArc<FunctionName>, Vec<Signature>> = module
+        .functions
+        .iter()
+        .filter(|(_, f)| f.exported)
+        .map(|(f_name, _)| (f_name, Vec::new()))
+        .collect();
+
+    // Returns
+    for (f_name, f) in &module.functions {
+        let Some(f_sigs) = sigs.get_mut(f_name) else {
+            continue;
+        };
+        for block in &f.blocks {
+            let TerminatorOpcode::Ret(ret) = block.terminator.opcode.as_ref() else {
+                continue;
+            };
+            let Some(op) = &ret.operand else {
+                continue;
+            };
+            for a in points_to(op) {
+                match placeholders.get(a) {
+                    Some(Placeholder::Arg(g, arg)) if *g == f_name => {
+                        push(f_sigs, Signature::ReturnAliasesArg { arg: *arg })
+                    }
+                    Some(Placeholder::Contents(g, arg)) if *g == f_name => {
+                        push(f_sigs, Signature::ReturnAliasesArgReachable { arg: *arg })
+                    }
+                    Some(_) => push(f_sigs, inexpressible()),
+                    None => match &***a {
+                        Alloc::Heap(_) => push(
+                            f_sigs,
+                            Signature::ReturnAlloc {
+                                r#type: AllocType::Heap,
+                                size_arg: None,
+                                count_arg: None,
+                            },
+                        ),
+                        // No signature returns a particular function
+                        Alloc::Function(_) | Alloc::Opaque(_) | Alloc::Top => push(
+                            f_sigs,
+                            Signature::ReturnAlloc {
+                                r#type: AllocType::Top,
+                                size_arg: None,
+                                count_arg: None,
+                            },
+                        ),
+                        Alloc::Global(_) => push(
+                            f_sigs,
+                            Signature::ReturnPointsToGlobal {
+                                global: global(a).unwrap(),
+                            },
+                        ),
+                        // Dangling or null
+                        Alloc::Field(_) | Alloc::Null | Alloc::Stack(_) => (),
+                    },
+                }
+            }
+        }
+    }
+
+    // Effects on the pointees of arguments
+    for ((f_name, arg), (arg_alloc, _)) in &parameter_allocs {
+        let f_sigs = sigs.get_mut(f_name).unwrap();
+        for b in alloc_points_to.get(arg_alloc).unwrap_or(&empty) {
+            match placeholders.get(b) {
+                Some(Placeholder::Contents(g, src)) if *g == f_name && src != arg => push(
+                    f_sigs,
+                    Signature::ArgMemcpyArg {
+                        dst: *arg,
+                        src: *src,
+                    },
+                ),
+                // Initially, each argument points to its own contents
+                Some(Placeholder::Contents(g, src)) if *g == f_name && src == arg => (),
+                Some(_) => push(f_sigs, inexpressible()),
+                None => match &***b {
+                    Alloc::Heap(_) => push(f_sigs, Signature::ArgAlloc { arg: *arg }),
+                    Alloc::Global(_) => push(
+                        f_sigs,
+                        Signature::ArgPointsToGlobal {
+                            arg: *arg,
+                            global: global(b).unwrap(),
+                        },
+                    ),
+                    Alloc::Function(_) | Alloc::Opaque(_) | Alloc::Top => {
+                        push(f_sigs, inexpressible())
+                    }
+                    // Dangling or null
+                    Alloc::Field(_) | Alloc::Null | Alloc::Stack(_) => (),
+                },
+            }
+        }
+    }
+
+    // Calls through arguments, anywhere in the library
+    for f in module.functions.values() {
+        for block in &f.blocks {
+            let calls = block.instrs.iter().filter_map(|i| match i.opcode.as_ref() {
+                Opcode::Call(Call { callee, args }) => Some((callee, args)),
+                _ => None,
+            });
+            let invoke = match block.terminator.opcode.as_ref() {
+                TerminatorOpcode::Invoke(Invoke { callee, args, .. }) => Some((callee, args)),
+                _ => None,
+            };
+            for (callee, args) in calls.chain(invoke) {
+                let Callee::Operand(op) = callee else {
+                    continue;
+                };
+                for a in points_to(op) {
+                    let Some(Placeholder::Arg(g, arg)) = placeholders.get(a) else {
+                        continue;
+                    };
+                    // Forward the arguments of the call if they're all
+                    // parameters of the function that was passed the callee
+                    let forwarded = args
+                        .iter()
+                        .map(|arg| {
+                            points_to(arg)
+                                .iter()
+                                .find_map(|b| match placeholders.get(b) {
+                                    Some(Placeholder::Arg(h, i)) if h == g => Some(*i),
+                                    _ => None,
+                                })
+                        })
+                        .collect::<Option<Vec<_>>>()
+                        .unwrap_or_default();
+                    push(
+                        sigs.get_mut(g).unwrap(),
+                        Signature::CallsArg {
+                            arg: *arg,
+                            args: forwarded,
+                        },
+                    );
+                }
+            }
+        }
+    }
+
+    sigs.into_iter()
+        .map(|(f_name, f_sigs)| (format!("^{}$", regex::escape(f_name.get())), f_sigs))
+        .collect()
+}
@@ -0,0 +1,1231 @@
+// SPDX-License-Identifier: BSD-3-Clause
+//! Unification-based (Steensgaard-style) pointer analysis
+//!
+//! A fast, context- and flow-insensitive alternative to [`pointer::analysis`],
+//! producing [`OutputRelations`] of the same shape. Every value and every
+//! allocation belongs to an equivalence class of memory locations, and each
+//! class points to at most one other class. Assignments unify the classes
+//! that their sides point to, so each instruction is processed once, and the
+//! analysis runs in near-linear time using a union-find with union by rank
+//! and path compression. The results are sound but much less precise than
+//! those of the inclusion-based analysis, which makes them a baseline on
+//! modules too large for it.
+//!
+//! Functions are classes with parameters, a return value, and a slot for
+//! variable arguments. Calling a pointer unifies its arguments with those of
+//! every function that it may point to. Allocations from call sites of
+//! external functions (e.g., `malloc`) are distinguished by call site, as in
+//! the inclusion-based analysis.
+//!
+//! Differences from [`pointer::analysis`]:
+//!
+//! - Only [`Options::library`], [`Options::metrics`],
+//!   [`Options::opaque_externs`], and [`Options::sound_externs`] are used.
+//!   Assertions are not checked. An opaque allocation escapes as soon as its
+//!   class is unified with that of `Top`.
+//! - All functions are analyzed, not just reachable ones. Reachability is
+//!   computed afterwards, from the callgraph.
+//! - `null` isn't tracked, so operands never point to it.
+//! - Exceptions are collected into a single class, and `memcpy` doesn't use
+//!   allocation sizes.
+//!
+//! [`pointer::analysis`]: crate::analysis::pointer::analysis
+//! [`Options::library`]: crate::analysis::pointer::Options::library
+//! [`Options::metrics`]: crate::analysis::pointer::Options::metrics
+//! [`Options::opaque_externs`]: crate::analysis::pointer::Options::opaque_externs
+//! [`Options::sound_externs`]: crate::analysis::pointer::Options::sound_externs
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    alloc::{Alloc, FunctionAlloc, GlobalAlloc, HeapAlloc, OpaqueAlloc, StackAlloc},
+    analysis::{
+        callgraph::{constant_functions, indirect_call_targets},
+        pointer::{Metrics, Options, OutputRelations},
+        summary,
+    },
+    arc::{Arc, UArc},
+    context::Context,
+    klimited::KLimited,
+    llvm::constant::Constant,
+    llvm::instruction::{
+        Add, AtomicRMW, BitCast, Call, CmpXchg, ExtractElement, ExtractValue, GetElementPtr,
+        InsertElement, InsertValue, IntToPtr, Load, Opcode, Phi, PtrToInt, Select, ShuffleVector,
+        Store, Sub, VAArg,
+    },
+    llvm::{
+        BlockName, Callee, FunctionName, GlobalName, InstructionName, InstructionOperand, Invoke,
+        Module, Operand, Resume, Ret, TerminatorOpcode,
+    },
+    signatures::{AllocType, Signature, Signatures},
+};
+
+/// Parameters, return value, and variable arguments of the functions in a
+/// class. These are classes of values, i.e., of the locations holding them.
+#[derive(Clone, Debug)]
+struct Lambda {
+    params: Vec<usize>,
+    ret: usize,
+    rest: usize,
+}
+
+#[derive(Debug)]
+struct Node {
+    parent: usize,
+    rank: u32,
+    /// Only meaningful for roots
+    pointee: Option<usize>,
+    /// Only meaningful for roots
+    lambda: Option<Lambda>,
+    /// Only meaningful for roots
+    allocs: Vec<Arc<Alloc>>,
+}
+
+/// Equivalence classes of locations, and the points-to edges between them
+#[derive(Debug, Default)]
+struct Graph {
+    nodes: Vec<Node>,
+}
+
+impl Graph {
+    fn fresh(&mut self) -> usize {
+        let n = self.nodes.len();
+        self.nodes.push(Node {
+            parent: n,
+            rank: 0,
+            pointee: None,
+            lambda: None,
+            allocs: Vec::new(),
+        });
+        n
+    }
+
+    fn alloc(&mut self, a: Arc<Alloc>) -> usize {
+        let n = self.fresh();
+        self.nodes[n].allocs.push(a);
+        n
+    }
+
+    fn find(&mut self, mut n: usize) -> usize {
+        // Path halving
+        while self.nodes[n].parent != n {
+            let grandparent = self.nodes[self.nodes[n].parent].parent;
+            self.nodes[n].parent = grandparent;
+            n = grandparent;
+        }
+        n
+    }
+
+    /// The class that `n` points to, created if `n` doesn't point to anything
+    fn pointee(&mut self, n: usize) -> usize {
+        let root = self.find(n);
+        match self.nodes[root].pointee {
+            Some(p) => p,
+            None => {
+                let p = self.fresh();
+                self.nodes[root].pointee = Some(p);
+                p
+            }
+        }
+    }
+
+    /// Unify the classes of `a` and `b`, and (recursively) the classes that
+    /// they point to
+    fn join(&mut self, a: usize, b: usize) {
+        let mut pending = vec![(a, b)];
+        while let Some((a, b)) = pending.pop() {
+            let (a, b) = (self.find(a), self.find(b));
+            if a == b {
+                continue;
+            }
+            let (root, child) = if self.nodes[a].rank < self.nodes[b].rank {
+                (b, a)
+            } else {
+                (a, b)
+            };
+            if self.nodes[a].rank == self.nodes[b].rank {
+                self.nodes[root].rank += 1;
+            }
+            self.nodes[child].parent = root;
+
+            let mut allocs = std::mem::take(&mut self.nodes[child].allocs);
+            if allocs.len() > self.nodes[root].allocs.len() {
+                std::mem::swap(&mut allocs, &mut self.nodes[root].allocs);
+            }
+            self.nodes[root].allocs.extend(allocs);
+
+            match (self.nodes[root].pointee, self.nodes[child].pointee.take()) {
+                (_, None) => (),
+                (None, Some(q)) => self.nodes[root].pointee = Some(q),
+                (Some(p), Some(q)) => pending.push((p, q)),
+            }
+
+            match (
+                self.nodes[root].lambda.take(),
+                self.nodes[child].lambda.take(),
+            ) {
+                (l, None) | (None, l) => self.nodes[root].lambda = l,
+                (Some(l), Some(m)) => {
+                    let len = l.params.len().max(m.params.len());
+                    let mut params = Vec::with_capacity(len);
+                    for i in 0..len {
+                        match (l.params.get(i), m.params.get(i)) {
+                            (Some(p), Some(q)) => {
+                                pending.push((*p, *q));
+                                params.push(*p);
+                            }
+                            (Some(p), None) => {
+                                pending.push((*p, m.rest));
+                                params.push(*p);
+                            }
+                            (None, Some(q)) => {
+                                pending.push((l.rest, *q));
+                                params.push(*q);
+                            }
+                            (None, None) => unreachable!(),
+                        }
+                    }
+                    pending.push((l.ret, m.ret));
+                    pending.push((l.rest, m.rest));
+                    self.nodes[root].lambda = Some(Lambda {
+                        params,
+                        ret: l.ret,
+                        rest: l.rest,
+                    });
+                }
+            }
+        }
+    }
+
+    /// The allocations that `n` points to, given the root of each class
+    fn pointees(&self, roots: &[usize], n: usize) -> &[Arc<Alloc>] {
+        match self.nodes[roots[n]].pointee {
+            Some(p) => &self.nodes[roots[p]].allocs,
+            None => &[],
+        }
+    }
+
+    /// Whether the class of `root` may hold memory from unknown code other
+    /// than `Top`, see [`Options::opaque_externs`]
+    fn is_opaque(&self, root: usize) -> bool {
+        self.nodes[root]
+            .allocs
+            .iter()
+            .any(|a| matches!(&**a, Alloc::Opaque(_)))
+    }
+
+    /// `dst` points to `target`
+    fn points_to(&mut self, dst: usize, target: usize) {
+        let p = self.pointee(dst);
+        self.join(p, target);
+    }
+
+    /// `dst = src`
+    fn assign(&mut self, dst: usize, src: usize) {
+        let p = self.pointee(dst);
+        let q = self.pointee(src);
+        self.join(p, q);
+    }
+
+    /// `dst = *src`
+    fn load(&mut self, dst: usize, src: usize) {
+        let p = self.pointee(src);
+        self.assign(dst, p);
+    }
+
+    /// `*dst = src`
+    fn store(&mut self, dst: usize, src: usize) {
+        let p = self.pointee(dst);
+        self.assign(p, src);
+    }
+
+    /// Unify everything reachable from `n`
+    fn collapse(&mut self, n: usize) {
+        let p = self.pointee(n);
+        let q = self.pointee(p);
+        self.join(p, q);
+    }
+
+    /// The parameters, return value, and variable arguments of the functions
+    /// in the class of `n`, created if there are none
+    fn lambda(&mut self, n: usize, nparams: usize) -> Lambda {
+        let root = self.find(n);
+        if let Some(l) = &self.nodes[root].lambda {
+            return l.clone();
+        }
+        let l = Lambda {
+            params: (0..nparams).map(|_| self.fresh()).collect(),
+            ret: self.fresh(),
+            rest: self.fresh(),
+        };
+        self.nodes[root].lambda = Some(l.clone());
+        l
+    }
+
+    /// Call the functions in the class of `n`
+    fn call(&mut self, n: usize, args: &[usize], ret: Option<usize>) {
+        let l = self.lambda(n, args.len());
+        for (i, arg) in args.iter().enumerate() {
+            self.assign(*l.params.get(i).unwrap_or(&l.rest), *arg);
+        }
+        if let Some(r) = ret {
+            self.assign(r, l.ret);
+        }
+    }
+}
+
+fn is_memcpy(f: &FunctionName) -> bool {
+    *f == "memcpy"
+        || *f == "__memcpy_chk"
+        || f.starts_with("llvm.memcpy")
+        || f.starts_with("llvm.memmove")
+        || *f == "llvm.va_copy"
+}
+
+#[derive(Debug)]
+enum Target {
+    Direct(UArc<FunctionName>),
+    /// Calls the functions that the value `callee` points to
+    Indirect {
+        callee: usize,
+        args: Vec<usize>,
+        ret: Option<usize>,
+    },
+    /// Calls the functions in the class `n` with unknown arguments, see
+    /// [`Signature::Unknown`]
+    Unknown(usize),
+}
+
+#[derive(Debug)]
+struct Site {
+    caller: UArc<FunctionName>,
+    /// `None` for effects of signatures applied to a function as a whole,
+    /// rather than at a call site
+    instr: Option<UArc<InstructionName>>,
+    target: Target,
+}
+
+struct Steensgaard<'a> {
+    module: &'a Module,
+    opts: &'a Options,
+    sigs: HashMap<UArc<FunctionName>, Vec<Signature>>,
+    graph: Graph,
+    empty_ctx: Arc<Context>,
+    /// The class of `Top`, which points to itself
+    top: usize,
+    /// A value that points to `Top`
+    top_value: usize,
+    /// A value that points to all thrown exceptions
+    exception: usize,
+    values: HashMap<Arc<Operand>, usize>,
+    /// Classes of functions, i.e., of their [`Alloc::Function`]
+    functions: HashMap<UArc<FunctionName>, usize>,
+    /// The parameters, return value, and variable arguments of each function
+    lambdas: HashMap<UArc<FunctionName>, Lambda>,
+    globals: HashMap<Arc<GlobalName>, usize>,
+    /// Allocations that are the same every time, see
+    /// [`Signature::ReturnAllocOnce`]
+    once: HashMap<(UArc<FunctionName>, usize), usize>,
+    sites: Vec<Site>,
+    // For metrics
+    frees: Vec<Arc<Operand>>,
+    callees: Vec<Arc<Operand>>,
+    loads: Vec<Arc<Operand>>,
+    memcpys: Vec<(Arc<Operand>, Arc<Operand>)>,
+    stores: Vec<Arc<Operand>>,
+}
+
+impl<'a> Steensgaard<'a> {
+    fn new(module: &'a Module, signatures: &Signatures, opts: &'a Options) -> Self {
+        let mut sigs = HashMap::new();
+        for f in module.decls.keys() {
+            if let Some(s) = signatures.signatures_for((*f).get()) {
+                sigs.insert(f.clone(), s);
+            }
+        }
+        let mut graph = Graph::default();
+        let top = graph.alloc(Arc::new(Alloc::Top));
+        graph.points_to(top, top);
+        let top_value = graph.fresh();
+        graph.points_to(top_value, top);
+        let exception = graph.fresh();
+        Steensgaard {
+            module,
+            opts,
+            sigs,
+            graph,
+            empty_ctx: Arc::new(KLimited::new(0, vec![])),
+            top,
+            top_value,
+            exception,
+            values: HashMap::new(),
+            functions: HashMap::new(),
+            lambdas: HashMap::new(),
+            globals: HashMap::new(),
+            once: HashMap::new(),
+            sites: Vec::new(),
+            frees: Vec::new(),
+            callees: Vec::new(),
+            loads: Vec::new(),
+            memcpys: Vec::new(),
+            stores: Vec::new(),
+        }
+    }
+
+    fn needs_signature(&self, f: &UArc<FunctionName>) -> bool {
+        !self.sigs.contains_key(f) && self.module.decls.get(f).is_some_and(|d| d.has_pointer())
+    }
+
+    /// Whether the signatures of this function say it doesn't throw
+    fn nothrow(&self, f: &UArc<FunctionName>) -> bool {
+        self.sigs
+            .get(f)
+            .is_some_and(|s| s.contains(&Signature::Nothrow))
+    }
+
+    fn returns_pointer(&self, f: &UArc<FunctionName>) -> bool {
+        self.module
+            .decls
+            .get(f)
+            .is_some_and(|d| matches!(*d.return_type, llvm_ir::Type::PointerType { .. }))
+    }
+
+    fn global(&mut self, g_name: &Arc<GlobalName>) -> usize {
+        if let Some(n) = self.globals.get(g_name) {
+            return *n;
+        }
+        let alloc = match self.module.globals.get(g_name) {
+            Some(g) => GlobalAlloc::new(g_name.clone(), g.is_const, g.size(self.module.layout())),
+            None => GlobalAlloc::new(g_name.clone(), false, None),
+        };
+        let n = self.graph.alloc(Arc::new(Alloc::Global(alloc)));
+        self.globals.insert(g_name.clone(), n);
+        n
+    }
+
+    fn function(&mut self, f: &UArc<FunctionName>) -> usize {
+        if let Some(n) = self.functions.get(f) {
+            return *n;
+        }
+        let n = self
+            .graph
+            .alloc(Arc::new(Alloc::Function(FunctionAlloc::new(f.clone()))));
+        self.functions.insert(f.clone(), n);
+        match self.module.functions.get(f) {
+            Some(func) => {
+                let lambda = Lambda {
+                    params: func.parameters.iter().map(|p| self.value(p)).collect(),
+                    ret: self.graph.fresh(),
+                    rest: self.graph.fresh(),
+                };
+                self.graph.nodes[n].lambda = Some(lambda.clone());
+                self.lambdas.insert(f.clone(), lambda);
+            }
+            None => {
+                let nparams = self.module.decls.get(f).map_or(0, |d| d.parameters.len());
+                let lambda = self.graph.lambda(n, nparams);
+                self.lambdas.insert(f.clone(), lambda.clone());
+                // Calls through pointers to declarations get the effects of
+                // their signatures, with allocations shared by all such calls.
+                self.external(f, f, None, &lambda.params, &[], Some(lambda.ret));
+            }
+        }
+        n
+    }
+
+    /// A value that points to the allocations that `c` points to
+    fn constant(&mut self, c: &Constant) -> usize {
+        let n = self.graph.fresh();
+        for p in c.pointers() {
+            match &p {
+                Constant::Function(f) => {
+                    let obj = self.function(f);
+                    self.graph.points_to(n, obj);
+                }
+                Constant::Global(g) => match self.module.aliases.get(g) {
+                    Some(aliasee) => {
+                        let v = self.constant(aliasee);
+                        self.graph.assign(n, v);
+                    }
+                    None => {
+                        let obj = self.global(g);
+                        self.graph.points_to(n, obj);
+                    }
+                },
+                _ => (),
+            }
+        }
+        n
+    }
+
+    fn value(&mut self, op: &Arc<Operand>) -> usize {
+        if let Some(n) = self.values.get(op) {
+            return *n;
+        }
+        let n = match &**op {
+            Operand::Constant(c) => self.constant(c),
+            Operand::Local(_) | Operand::Metadata => self.graph.fresh(),
+        };
+        self.values.insert(op.clone(), n);
+        n
+    }
+
+    fn instruction(&mut self, i: &UArc<InstructionName>) -> usize {
+        self.value(&InstructionOperand::new(i.clone()).operand())
+    }
+
+    /// An allocation made by the `idx`th signature of `f`, at a call site or
+    /// (if `site` is `None`) the same every time
+    fn signature_alloc(
+        &mut self,
+        f: &UArc<FunctionName>,
+        idx: usize,
+        r#type: &AllocType,
+        site: Option<&UArc<InstructionName>>,
+        size: Option<u64>,
+    ) -> usize {
+        let site = match (site, r#type) {
+            (_, AllocType::Top) => return self.top,
+            (Some(site), AllocType::Heap) => {
+                let alloc = HeapAlloc::new(site.clone(), self.empty_ctx.clone(), size);
+                return self.graph.alloc(Arc::new(Alloc::Heap(alloc)));
+            }
+            (Some(site), AllocType::Stack) => {
+                let alloc = StackAlloc::signature(site.clone(), self.empty_ctx.clone());
+                return self.graph.alloc(Arc::new(Alloc::Stack(alloc)));
+            }
+            (None, _) => {
+                if let Some(n) = self.once.get(&(f.clone(), idx)) {
+                    return *n;
+                }
+                let block = UArc::new(BlockName::signature(f.clone()));
+                UArc::new(InstructionName::new(f.clone(), block, idx))
+            }
+        };
+        let alloc = match r#type {
+            AllocType::Stack => Alloc::Stack(StackAlloc::signature(site, self.empty_ctx.clone())),
+            _ => Alloc::Heap(HeapAlloc::new(site, self.empty_ctx.clone(), None)),
+        };
+        let n = self.graph.alloc(Arc::new(alloc));
+        self.once.insert((f.clone(), idx), n);
+        n
+    }
+
+    /// Unknown effects, see [`Signature::Unknown`]
+    #[allow(clippy::too_many_arguments)]
+    fn unknown(
+        &mut self,
+        caller: &UArc<FunctionName>,
+        callee: &UArc<FunctionName>,
+        site: Option<&UArc<InstructionName>>,
+        args: &[usize],
+        ret: Option<usize>,
+        stores: bool,
+        calls: bool,
+    ) {
+        if let Some(r) = ret {
+            if self.returns_pointer(callee) {
+                let target = match site {
+                    Some(site) if self.opts.opaque_externs => {
+                        let opaque = OpaqueAlloc::new(site.clone(), callee.clone());
+                        let n = self.graph.alloc(Arc::new(Alloc::Opaque(opaque)));
+                        self.graph.points_to(n, n);
+                        n
+                    }
+                    _ => self.top,
+                };
+                self.graph.points_to(r, target);
+            }
+        }
+        // Functions reachable from the arguments are merged into the class
+        // of `Top`, and so are called along with those passed directly.
+        for arg in args {
+            if stores || calls {
+                let p = self.graph.pointee(*arg);
+                let contents = self.graph.pointee(p);
+                self.graph.join(contents, self.top);
+            }
+            if calls {
+                let p = self.graph.pointee(*arg);
+                self.sites.push(Site {
+                    caller: caller.clone(),
+                    instr: site.cloned(),
+                    target: Target::Unknown(p),
+                });
+            }
+        }
+        if calls {
+            self.sites.push(Site {
+                caller: caller.clone(),
+                instr: site.cloned(),
+                target: Target::Unknown(self.top),
+            });
+        }
+    }
+
+    /// Effects of a call from `caller` to the external function `callee`
+    fn external(
+        &mut self,
+        caller: &UArc<FunctionName>,
+        callee: &UArc<FunctionName>,
+        site: Option<&UArc<InstructionName>>,
+        args: &[usize],
+        consts: &[Option<u64>],
+        ret: Option<usize>,
+    ) {
+        let name = callee.get();
+        if is_memcpy(callee) {
+            if let (Some(dst), Some(src)) = (args.first(), args.get(1)) {
+                let (dst, src) = (self.graph.pointee(*dst), self.graph.pointee(*src));
+                self.graph.assign(dst, src);
+            }
+        } else if name == "llvm.va_start" {
+            if let (Some(site), Some(ap)) = (site, args.first()) {
+                let alloc = StackAlloc::varargs(site.clone(), self.empty_ctx.clone());
+                let varargs = self.graph.alloc(Arc::new(Alloc::Stack(alloc)));
+                if let Some(lambda) = self.lambdas.get(caller) {
+                    self.graph.join(varargs, lambda.rest);
+                }
+                let va_list = self.graph.pointee(*ap);
+                self.graph.points_to(va_list, varargs);
+            }
+        } else if name == "__cxa_throw" {
+            if let Some(exn) = args.first() {
+                self.graph.assign(self.exception, *exn);
+            }
+        } else if name == "__cxa_begin_catch" || name == "__cxa_get_exception_ptr" {
+            if let Some(r) = ret {
+                self.graph.assign(r, self.exception);
+            }
+        }
+
+        let sigs = match self.sigs.get(callee) {
+            Some(s) => s.clone(),
+            None => {
+                if self.needs_signature(callee) {
+                    let sound = self.opts.sound_externs;
+                    self.unknown(caller, callee, site, args, ret, sound, sound);
+                }
+                return;
+            }
+        };
+        let arg = |i: &usize| args.get(*i).copied();
+        let arg_int = |i: &usize| consts.get(*i).copied().flatten();
+        for (idx, sig) in sigs.iter().enumerate() {
+            match sig {
+                Signature::CallsArg { arg: a, args: fwd } => {
+                    if let Some(a) = arg(a) {
+                        let fwd: Vec<_> = fwd.iter().filter_map(arg).collect();
+                        let callee = self.graph.pointee(a);
+                        self.graph.call(callee, &fwd, None);
+                        self.sites.push(Site {
+                            caller: caller.clone(),
+                            instr: site.cloned(),
+                            target: Target::Indirect {
+                                callee: a,
+                                args: fwd,
+                                ret: None,
+                            },
+                        });
+                    }
+                }
+                Signature::ReturnAlloc {
+                    r#type,
+                    size_arg,
+                    count_arg,
+                } => {
+                    if let Some(r) = ret {
+                        let size = size_arg.and_then(|i| arg_int(&i));
+                        let size = match count_arg {
+                            None => size,
+                            Some(i) => size.zip(arg_int(i)).and_then(|(s, c)| s.checked_mul(c)),
+                        };
+                        let a = self.signature_alloc(callee, idx, r#type, site, size);
+                        self.graph.points_to(r, a);
+                    }
+                }
+                Signature::ReturnAllocOnce { r#type } => {
+                    if let Some(r) = ret {
+                        let a = self.signature_alloc(callee, idx, r#type, None, None);
+                        self.graph.points_to(r, a);
+                    }
+                }
+                Signature::ReturnAliasesArg { arg: a } => {
+                    if let (Some(r), Some(a)) = (ret, arg(a)) {
+                        self.graph.assign(r, a);
+                    }
+                }
+                Signature::ReturnAliasesArgReachable { arg: a } => {
+                    if let (Some(r), Some(a)) = (ret, arg(a)) {
+                        self.graph.collapse(a);
+                        self.graph.assign(r, a);
+                    }
+                }
+                Signature::ReturnPointsToGlobal { global } => {
+                    if let Some(r) = ret {
+                        let g = self.global(&Arc::new(GlobalName::from(global.as_ref())));
+                        self.graph.points_to(r, g);
+                    }
+                }
+                Signature::ReturnAliasesGlobal { global } => {
+                    if let Some(r) = ret {
+                        let g = self.global(&Arc::new(GlobalName::from(global.as_ref())));
+                        self.graph.assign(r, g);
+                    }
+                }
+                Signature::ReturnAliasesGlobalReachable { global } => {
+                    if let Some(r) = ret {
+                        let g = self.global(&Arc::new(GlobalName::from(global.as_ref())));
+                        self.graph.collapse(g);
+                        self.graph.assign(r, g);
+                    }
+                }
+                Signature::ArgAlloc { arg: a } => {
+                    if let Some(a) = arg(a) {
+                        let alloc = self.signature_alloc(callee, idx, &AllocType::Heap, site, None);
+                        let p = self.graph.pointee(a);
+                        self.graph.points_to(p, alloc);
+                    }
+                }
+                Signature::ArgAllocOnce { arg: a } => {
+                    if let Some(a) = arg(a) {
+                        let alloc = self.signature_alloc(callee, idx, &AllocType::Heap, None, None);
+                        let p = self.graph.pointee(a);
+                        self.graph.points_to(p, alloc);
+                    }
+                }
+                Signature::ArgMemcpyArg { dst, src } => {
+                    if let (Some(dst), Some(src)) = (arg(dst), arg(src)) {
+                        let (dst, src) = (self.graph.pointee(dst), self.graph.pointee(src));
+                        self.graph.assign(dst, src);
+                    }
+                }
+                Signature::ArgMemcpyArgReachable { dst, src } => {
+                    if let (Some(dst), Some(src)) = (arg(dst), arg(src)) {
+                        self.graph.collapse(src);
+                        let (dst, src) = (self.graph.pointee(dst), self.graph.pointee(src));
+                        self.graph.assign(dst, src);
+                    }
+                }
+                Signature::ArgMemcpyGlobal { dst, global } => {
+                    if let Some(dst) = arg(dst) {
+                        let g = self.global(&Arc::new(GlobalName::from(global.as_ref())));
+                        let dst = self.graph.pointee(dst);
+                        self.graph.assign(dst, g);
+                    }
+                }
+                Signature::ArgMemcpyGlobalReachable { dst, global } => {
+                    if let Some(dst) = arg(dst) {
+                        let g = self.global(&Arc::new(GlobalName::from(global.as_ref())));
+                        self.graph.collapse(g);
+                        let dst = self.graph.pointee(dst);
+                        self.graph.assign(dst, g);
+                    }
+                }
+                Signature::ArgPointsToGlobal { arg: a, global } => {
+                    if let Some(a) = arg(a) {
+                        let g = self.global(&Arc::new(GlobalName::from(global.as_ref())));
+                        let p = self.graph.pointee(a);
+                        self.graph.points_to(p, g);
+                    }
+                }
+                // See `Steensgaard::globals_points_to_fresh`
+                Signature::GlobalPointsToFresh { .. } | Signature::Nothrow => (),
+                Signature::Unknown { stores, calls } => {
+                    self.unknown(caller, callee, site, args, ret, *stores, *calls);
+                }
+            }
+        }
+    }
+
+    fn call(
+        &mut self,
+        caller: &UArc<FunctionName>,
+        instr: &UArc<InstructionName>,
+        callee: &Callee,
+        args: &[Arc<Operand>],
+        ret: usize,
+    ) {
+        let Callee::Operand(op) = callee else {
+            return;
+        };
+        self.callees.push(op.clone());
+        let callee = self.value(op);
+        let arg_values: Vec<_> = args.iter().map(|a| self.value(a)).collect();
+        match &**op {
+            Operand::Constant(c) => {
+                let consts: Vec<_> = args.iter().map(|a| a.constant_int()).collect();
+                for f in constant_functions(self.module, c) {
+                    if self.module.functions.contains_key(&f) {
+                        let obj = self.function(&f);
+                        self.graph.call(obj, &arg_values, Some(ret));
+                    } else {
+                        if *f == "free" {
+                            self.frees.extend(args.first().cloned());
+                        }
+                        if is_memcpy(&f) {
+                            if let (Some(dst), Some(src)) = (args.first(), args.get(1)) {
+                                self.memcpys.push((dst.clone(), src.clone()));
+                            }
+                        }
+                        self.external(caller, &f, Some(instr), &arg_values, &consts, Some(ret));
+                    }
+                    self.sites.push(Site {
+                        caller: caller.clone(),
+                        instr: Some(instr.clone()),
+                        target: Target::Direct(f),
+                    });
+                }
+            }
+            Operand::Local(_) => {
+                let obj = self.graph.pointee(callee);
+                self.graph.call(obj, &arg_values, Some(ret));
+                self.sites.push(Site {
+                    caller: caller.clone(),
+                    instr: Some(instr.clone()),
+                    target: Target::Indirect {
+                        callee,
+                        args: arg_values,
+                        ret: Some(ret),
+                    },
+                });
+            }
+            Operand::Metadata => (),
+        }
+    }
+
+    fn opcode(&mut self, f_name: &UArc<FunctionName>, i: &UArc<InstructionName>, op: &Opcode) {
+        let v = self.instruction(i);
+        // Constants point to their allocations even where they don't flow
+        // anywhere, e.g., into comparisons
+        for operand in op.operands() {
+            self.value(&operand);
+        }
+        let pass_thru: Vec<&Arc<Operand>> = match op {
+            Opcode::Add(Add { operand0, operand1 }) => vec![operand0, operand1],
+            Opcode::BitCast(BitCast { pointer }) => vec![pointer],
+            Opcode::ExtractElement(ExtractElement { vector }) => vec![vector],
+            Opcode::ExtractValue(ExtractValue { aggregate, .. }) => vec![aggregate],
+            Opcode::GetElementPtr(GetElementPtr { pointer, .. }) => vec![pointer],
+            Opcode::InsertElement(InsertElement { vector, element }) => vec![vector, element],
+            Opcode::InsertValue(InsertValue {
+                aggregate, element, ..
+            }) => vec![aggregate, element],
+            Opcode::IntToPtr(IntToPtr { int }) => vec![int],
+            Opcode::Phi(Phi { values }) => values.iter().collect(),
+            Opcode::PtrToInt(PtrToInt { pointer }) => vec![pointer],
+            Opcode::Select(Select {
+                true_value,
+                false_value,
+            }) => vec![true_value, false_value],
+            Opcode::ShuffleVector(ShuffleVector { operand0, operand1 }) => {
+                vec![operand0, operand1]
+            }
+            Opcode::Sub(Sub { minuend, .. }) => vec![minuend],
+            Opcode::Alloca(a) => {
+                let alloc = StackAlloc::alloca(i.clone(), self.empty_ctx.clone(), a);
+                let obj = self.graph.alloc(Arc::new(Alloc::Stack(alloc)));
+                self.graph.points_to(v, obj);
+                vec![]
+            }
+            Opcode::AtomicRMW(AtomicRMW { pointer, value }) => {
+                let (p, x) = (self.value(pointer), self.value(value));
+                self.graph.load(v, p);
+                self.graph.store(p, x);
+                self.loads.push(pointer.clone());
+                self.stores.push(pointer.clone());
+                vec![]
+            }
+            Opcode::Call(Call { callee, args }) => {
+                self.call(f_name, i, callee, args, v);
+                vec![]
+            }
+            Opcode::CmpXchg(CmpXchg {
+                pointer,
+                replacement,
+                ..
+            }) => {
+                let (p, x) = (self.value(pointer), self.value(replacement));
+                self.graph.load(v, p);
+                self.graph.store(p, x);
+                self.loads.push(pointer.clone());
+                self.stores.push(pointer.clone());
+                vec![]
+            }
+            Opcode::LandingPad => {
+                self.graph.assign(v, self.exception);
+                vec![]
+            }
+            Opcode::Load(Load { pointer }) => {
+                let p = self.value(pointer);
+                self.graph.load(v, p);
+                self.loads.push(pointer.clone());
+                vec![]
+            }
+            Opcode::Store(Store { value, pointer, .. }) => {
+                let (p, x) = (self.value(pointer), self.value(value));
+                self.graph.store(p, x);
+                self.stores.push(pointer.clone());
+                vec![]
+            }
+            Opcode::VAArg(VAArg { arg_list }) => {
+                // `arg_list` points to a `va_list`, which points to the
+                // varargs allocation
+                let l = self.value(arg_list);
+                let va_list = self.graph.pointee(l);
+                self.graph.load(v, va_list);
+                vec![]
+            }
+            Opcode::Icmp(_) | Opcode::Other => vec![],
+        };
+        for src in pass_thru {
+            let src = self.value(src);
+            self.graph.assign(v, src);
+        }
+    }
+
+    fn terminator(
+        &mut self,
+        f_name: &UArc<FunctionName>,
+        t: &UArc<InstructionName>,
+        op: &TerminatorOpcode,
+    ) {
+        for operand in op.operands() {
+            self.value(&operand);
+        }
+        match op {
+            TerminatorOpcode::Invoke(Invoke { callee, args, .. }) => {
+                let v = self.instruction(t);
+                self.call(f_name, t, callee, args, v);
+                // External functions not known not to throw may throw anything
+                if let Callee::Operand(op) = callee {
+                    if let Operand::Constant(c) = &**op {
+                        for f in constant_functions(self.module, c) {
+                            if self.module.decls.contains_key(&f) && !self.nothrow(&f) {
+                                self.graph.points_to(self.exception, self.top);
+                            }
+                        }
+                    }
+                }
+            }
+            TerminatorOpcode::Resume(Resume { operand }) => {
+                let v = self.value(operand);
+                self.graph.assign(self.exception, v);
+            }
+            TerminatorOpcode::Ret(Ret {
+                operand: Some(operand),
+            }) => {
+                let v = self.value(operand);
+                let ret = self.lambdas[f_name].ret;
+                self.graph.assign(ret, v);
+            }
+            TerminatorOpcode::Ret(Ret { operand: None }) | TerminatorOpcode::Other => (),
+        }
+    }
+
+    fn entries(&self) -> Vec<UArc<FunctionName>> {
+        self.module
+            .functions
+            .iter()
+            .filter(|(f_name, f)| {
+                ***f_name == "main"
+                    || f_name.starts_with("_ZN") && f_name.contains("4main")
+                    || self.opts.library && f.exported
+            })
+            .map(|(f_name, _)| f_name.clone())
+            .collect()
+    }
+
+    fn run(&mut self, signatures: &Signatures) {
+        let module = self.module;
+        for f_name in module.functions.keys() {
+            self.function(f_name);
+        }
+        for (f_name, f) in &module.functions {
+            for b in &f.blocks {
+                for i in &b.instrs {
+                    self.opcode(f_name, &i.name, i.opcode.as_ref());
+                }
+                self.terminator(f_name, &b.terminator.name, b.terminator.opcode.as_ref());
+            }
+        }
+
+        for (g_name, g) in &module.globals {
+            if let Some(init) = &g.initializer {
+                let obj = self.global(g_name);
+                let v = self.constant(init);
+                self.graph.assign(obj, v);
+            }
+        }
+        self.globals_points_to_fresh(signatures);
+
+        // argv
+        for f_name in self.entries() {
+            if let Some(argv) = module.functions[&f_name].parameters.get(1) {
+                let argv = self.value(argv);
+                let argv_alloc = self.global(&Arc::new(GlobalName::from("*argv")));
+                let argv0_alloc = self.global(&Arc::new(GlobalName::from("*argv[*]")));
+                self.graph.points_to(argv, argv_alloc);
+                self.graph.points_to(argv_alloc, argv0_alloc);
+            }
+        }
+
+        if self.opts.library {
+            for ((f_name, i), (arg_alloc, contents_alloc)) in summary::parameter_allocs(module) {
+                let param = self.value(&module.functions[&f_name].parameters[i]);
+                let arg = self.graph.alloc(arg_alloc);
+                let contents = self.graph.alloc(contents_alloc);
+                self.graph.points_to(param, arg);
+                self.graph.points_to(arg, contents);
+            }
+        }
+
+        self.resolve_top_calls();
+    }
+
+    /// See [`Signature::GlobalPointsToFresh`]
+    fn globals_points_to_fresh(&mut self, signatures: &Signatures) {
+        let module = self.module;
+        let global_sigs = module
+            .globals
+            .iter()
+            .filter(|(_, g)| g.initializer.is_none())
+            .filter_map(|(g_name, _)| signatures.signatures_for(g_name.get()));
+        let sigs: Vec<_> = self.sigs.values().cloned().chain(global_sigs).collect();
+        for sig in sigs.into_iter().flatten() {
+            if let Signature::GlobalPointsToFresh { global } = sig {
+                let g_name = Arc::new(GlobalName::from(global.as_ref()));
+                if module
+                    .globals
+                    .get(&g_name)
+                    .is_none_or(|g| g.initializer.is_none())
+                {
+                    let obj = self.global(&g_name);
+                    let fresh = GlobalName::from(format!("*@{global}").as_ref());
+                    let fresh = self.global(&Arc::new(fresh));
+                    self.graph.points_to(obj, fresh);
+                }
+            }
+        }
+    }
+
+    /// Calls through `Top` may call any function, and functions passed to
+    /// functions with unknown effects may be called with unknown arguments.
+    /// Both may put more functions in the class of `Top`, so repeat until
+    /// nothing changes.
+    fn resolve_top_calls(&mut self) {
+        let module = self.module;
+        let mut done: HashSet<(usize, UArc<FunctionName>)> = HashSet::new();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            let top = self.graph.find(self.top);
+            for idx in 0..self.sites.len() {
+                let calls: Vec<(UArc<FunctionName>, Vec<usize>, Option<usize>)> =
+                    match &self.sites[idx].target {
+                        Target::Direct(_) => continue,
+                        Target::Indirect { callee, args, ret } => {
+                            let (callee, args, ret) = (*callee, args.clone(), *ret);
+                            let obj = self.graph.pointee(callee);
+                            let obj = self.graph.find(obj);
+                            if obj != top && !self.graph.is_opaque(obj) {
+                                continue;
+                            }
+                            indirect_call_targets(module, args.len())
+                                .map(|f| (f, args.clone(), ret))
+                                .collect()
+                        }
+                        Target::Unknown(n) => {
+                            let root = self.graph.find(*n);
+                            self.graph.nodes[root]
+                                .allocs
+                                .iter()
+                                .filter_map(|a| match &**a {
+                                    Alloc::Function(f) => Some(f.function_name()),
+                                    _ => None,
+                                })
+                                .map(|f| {
+                                    let nparams = module
+                                        .functions
+                                        .get(&f)
+                                        .map_or(0, |func| func.parameters.len());
+                                    (f, vec![self.top_value; nparams], None)
+                                })
+                                .collect()
+                        }
+                    };
+                for (f, args, ret) in calls {
+                    if done.insert((idx, f.clone())) {
+                        let obj = self.function(&f);
+                        self.graph.call(obj, &args, ret);
+                        changed = true;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Functions that a call site may call
+    fn targets(&self, site: &Site, roots: &[usize]) -> Vec<UArc<FunctionName>> {
+        let class = match &site.target {
+            Target::Direct(f) => return vec![f.clone()],
+            Target::Indirect { callee, .. } => match self.graph.nodes[roots[*callee]].pointee {
+                Some(p) => roots[p],
+                None => return Vec::new(),
+            },
+            Target::Unknown(n) => roots[*n],
+        };
+        let mut targets: Vec<_> = self.graph.nodes[class]
+            .allocs
+            .iter()
+            .filter_map(|a| match &**a {
+                Alloc::Function(f) => Some(f.function_name()),
+                _ => None,
+            })
+            .collect();
+        if let Target::Indirect { args, .. } = &site.target {
+            if class == roots[self.top] || self.graph.is_opaque(class) {
+                targets.extend(indirect_call_targets(self.module, args.len()));
+            }
+        }
+        targets
+    }
+
+    fn outputs(mut self) -> OutputRelations {
+        let roots: Vec<usize> = (0..self.graph.nodes.len())
+            .map(|n| self.graph.find(n))
+            .collect();
+        let top = roots[self.top];
+
+        // Reachability and the callgraph
+        let mut sites_by_caller: HashMap<UArc<FunctionName>, Vec<usize>> = HashMap::new();
+        for (idx, site) in self.sites.iter().enumerate() {
+            sites_by_caller
+                .entry(site.caller.clone())
+                .or_default()
+                .push(idx);
+        }
+        let mut reachable: HashSet<UArc<FunctionName>> = HashSet::new();
+        let mut worklist = self.entries();
+        let mut callgraph: HashSet<(UArc<InstructionName>, UArc<FunctionName>)> = HashSet::new();
+        while let Some(f) = worklist.pop() {
+            if !reachable.insert(f.clone()) {
+                continue;
+            }
+            for idx in sites_by_caller.get(&f).cloned().unwrap_or_default() {
+                for callee in self.targets(&self.sites[idx], &roots) {
+                    if let Some(instr) = &self.sites[idx].instr {
+                        callgraph.insert((instr.clone(), callee.clone()));
+                    }
+                    worklist.push(callee);
+                }
+            }
+        }
+        let calls = callgraph
+            .iter()
+            .map(|(i, f)| ((self.empty_ctx.clone(), i.clone()), f.clone()))
+            .collect();
+
+        let mut operand_points_to = Vec::new();
+        for (op, n) in &self.values {
+            for a in self.graph.pointees(&roots, *n) {
+                operand_points_to.push((self.empty_ctx.clone(), op.clone(), a.clone()));
+            }
+        }
+        let mut alloc_points_to = Vec::new();
+        for (n, node) in self.graph.nodes.iter().enumerate() {
+            if roots[n] != n {
+                continue;
+            }
+            let targets = self.graph.pointees(&roots, n);
+            for a in node.allocs.iter().filter(|a| a.loadable()) {
+                for b in targets {
+                    alloc_points_to.push((a.clone(), b.clone()));
+                }
+            }
+        }
+
+        let metrics = if self.opts.metrics {
+            let invalid = |ops: &[Arc<Operand>], valid: fn(&Alloc) -> bool| {
+                let mut pairs = HashSet::new();
+                for op in ops {
+                    if let Some(n) = self.values.get(op) {
+                        for a in self.graph.pointees(&roots, *n) {
+                            if !valid(a) {
+                                pairs.insert((op.clone(), a.clone()));
+                            }
+                        }
+                    }
+                }
+                pairs.len()
+            };
+            let dsts: Vec<_> = self.memcpys.iter().map(|(d, _)| d.clone()).collect();
+            let srcs: Vec<_> = self.memcpys.iter().map(|(_, s)| s.clone()).collect();
+            let points_to_top = self
+                .values
+                .iter()
+                .filter(|(_, n)| {
+                    self.graph.nodes[roots[**n]]
+                        .pointee
+                        .is_some_and(|p| roots[p] == top)
+                })
+                .count();
+            let points_to_opaque = self
+                .values
+                .iter()
+                .filter(|(_, n)| {
+                    self.graph
+                        .pointees(&roots, **n)
+                        .iter()
+                        .any(|a| matches!(&**a, Alloc::Opaque(_)))
+                })
+                .count();
+            let free_non_heap: HashSet<_> = self
+                .frees
+                .iter()
+                .filter_map(|op| self.values.get(op))
+                .flat_map(|n| self.graph.pointees(&roots, *n))
+                .filter(|a| !a.freeable())
+                .collect();
+            Some(Metrics {
+                callgraph_size: callgraph.len(),
+                free_non_heap: free_non_heap.len(),
+                invalid_calls: invalid(&self.callees, |a| matches!(a, Alloc::Function(_))),
+                invalid_loads: invalid(&self.loads, Alloc::loadable),
+                invalid_memcpy_dsts: invalid(&dsts, Alloc::storable),
+                invalid_memcpy_srcs: invalid(&srcs, Alloc::loadable),
+                invalid_stores: invalid(&self.stores, Alloc::storable),
+                points_to_top,
+                points_to_opaque,
+                widened: 0,
+            })
+        } else {
+            None
+        };
+
+        let needs_signature = self
+            .module
+            .decls
+            .keys()
+            .filter(|f| self.needs_signature(f))
+            .cloned()
+            .collect();
+
+        OutputRelations {
+            alloc_points_to,
+            operand_points_to,
+            reachable: reachable.into_iter().collect(),
+            callgraph: callgraph.into_iter().collect(),
+            calls,
+            needs_signature,
+            metrics,
+        }
+    }
+}
+
+/// Unification-based pointer analysis, see the module documentation
+pub fn analysis(module: &Module, signatures: &Signatures, opts: &Options) -> OutputRelations {
+    let mut analysis = Steensgaard::new(module, signatures, opts);
+    analysis.run(signatures);
+    analysis.outputs()
+}
@@ -0,0 +1,167 @@
+// SPDX-License-Identifier: BSD-3-Clause
+//! Widening of points-to sets that grow past a size budget
+//!
+//! On large programs, a few operands and allocations accumulate thousands of
+//! allocations, and the `load` and `store` rules that join on them dominate
+//! the running time. With a [`Widening`], once the allocations loaded into an
+//! operand (in some context), or stored into an allocation, exceed the
+//! budget, further ones are replaced by a single stand-in: `Top`, or a
+//! summary allocation shared by all widened pointers of the same type. These
+//! points-to sets then hold at most one more allocation than the budget.
+//!
+//! The stand-in aliases the allocations it replaces, its *members*: the
+//! analysis flows the contents of each member into the stand-in and vice
+//! versa, so loads through one pointer still see stores through another. The
+//! allocations that were already in a set when it collapsed become members
+//! too, and are dropped from the reported points-to sets that contain their
+//! stand-in, so the results don't depend on the order in which allocations
+//! reached the set.
+//!
+//! `null`, functions, and `Top` itself are never widened. They can't be loaded
+//! from or stored to, so they don't make loads and stores more expensive, and
+//! keeping them preserves nullability and the callgraph.
+
+use std::collections::HashSet;
+use std::hash::Hash;
+
+use dashmap::DashMap;
+
+use crate::{
+    alloc::{Alloc, GlobalAlloc},
+    arc::Arc,
+    context::Context,
+    llvm::{GlobalName, Operand},
+};
+
+/// What widened points-to sets collapse to
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Summary {
+    /// The special `Top` allocation
+    Top,
+    /// A summary allocation per pointer type, named `*widened:<type>`
+    Type,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Widening {
+    /// Maximum number of allocations loaded into an operand or stored into an
+    /// allocation (of each pointer type, for [`Summary::Type`]) before
+    /// widening
+    pub budget: usize,
+    pub summary: Summary,
+}
+
+/// A stand-in and one of its members
+pub(crate) type Member = (Arc<Alloc>, Arc<Alloc>);
+
+#[derive(Default)]
+struct Set {
+    allocs: HashSet<Arc<Alloc>>,
+    collapsed: bool,
+}
+
+/// Points-to sets by key and stand-in
+type Sets<K> = DashMap<(K, Arc<Alloc>), Set>;
+
+pub(crate) struct Widener {
+    widening: Widening,
+    top: Arc<Alloc>,
+    operands: Sets<(Arc<Context>, Arc<Operand>)>,
+    allocs: Sets<Arc<Alloc>>,
+    summaries: DashMap<llvm_ir::TypeRef, Arc<Alloc>>,
+}
+
+impl Widener {
+    pub(crate) fn new(widening: Widening, top: Arc<Alloc>) -> Self {
+        Widener {
+            widening,
+            top,
+            operands: DashMap::new(),
+            allocs: DashMap::new(),
+            summaries: DashMap::new(),
+        }
+    }
+
+    /// Allocations to add to the points-to set of `op` in `ctx`, loaded from
+    /// a pointer to `a` of type `ty`, each with the member it stands for (see
+    /// [`Widener::widen`])
+    pub(crate) fn operand(
+        &self,
+        ctx: &Arc<Context>,
+        op: Arc<Operand>,
+        ty: &llvm_ir::TypeRef,
+        a: Arc<Alloc>,
+    ) -> Vec<Member> {
+        self.widen(&self.operands, (ctx.clone(), op), ty, a)
+    }
+
+    /// Allocations to add to the points-to set of `pointer_alloc`, for a
+    /// store of a pointer to `a` of type `ty`, each with the member it stands
+    /// for (see [`Widener::widen`])
+    pub(crate) fn alloc(
+        &self,
+        pointer_alloc: &Arc<Alloc>,
+        ty: &llvm_ir::TypeRef,
+        a: Arc<Alloc>,
+    ) -> Vec<Member> {
+        self.widen(&self.allocs, pointer_alloc.clone(), ty, a)
+    }
+
+    /// Number of points-to sets that were collapsed
+    pub(crate) fn collapsed(&self) -> usize {
+        let operands = self.operands.iter().filter(|s| s.collapsed).count();
+        operands + self.allocs.iter().filter(|s| s.collapsed).count()
+    }
+
+    /// `a` itself (paired with itself) while the set of `key` is within the
+    /// budget. Past it, the stand-in for `ty` paired with `a`, and when the
+    /// set collapses, also with each allocation already in it. Sets are kept
+    /// per stand-in, so that each allocation in a set is a member of the
+    /// stand-in that replaces the others.
+    fn widen<K: Eq + Hash>(
+        &self,
+        sets: &Sets<K>,
+        key: K,
+        ty: &llvm_ir::TypeRef,
+        a: Arc<Alloc>,
+    ) -> Vec<Member> {
+        if matches!(
+            &*a,
+            Alloc::Function(_) | Alloc::Null | Alloc::Opaque(_) | Alloc::Top
+        ) {
+            return vec![(a.clone(), a)];
+        }
+        let summary = self.summary(ty);
+        let mut set = sets.entry((key, summary.clone())).or_default();
+        if set.allocs.contains(&a) {
+            return vec![(a.clone(), a)];
+        }
+        if !set.collapsed && set.allocs.len() < self.widening.budget {
+            set.allocs.insert(a.clone());
+            return vec![(a.clone(), a)];
+        }
+        let mut members = vec![(summary.clone(), a)];
+        if !set.collapsed {
+            set.collapsed = true;
+            members.extend(set.allocs.iter().map(|m| (summary.clone(), m.clone())));
+        }
+        members
+    }
+
+    fn summary(&self, ty: &llvm_ir::TypeRef) -> Arc<Alloc> {
+        match self.widening.summary {
+            Summary::Top => self.top.clone(),
+            Summary::Type => self
+                .summaries
+                .entry(ty.clone())
+                .or_insert_with(|| {
+                    Arc::new(Alloc::Global(GlobalAlloc::new(
+                        Arc::new(GlobalName::from(format!("*widened:{ty}").as_ref())),
+                        false,
+                        None,
+                    )))
+                })
+                .clone(),
+        }
+    }
+}
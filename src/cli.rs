@@ -19,22 +19,125 @@ impl fmt::Display for Check {
     }
 }
 
+#[derive(Clone, Debug, Eq, PartialEq, clap::ValueEnum)]
+pub enum ContextPolicy {
+    CallSite,
+    Object,
+    Type,
+}
+
+impl fmt::Display for ContextPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ContextPolicy::CallSite => write!(f, "call-site"),
+            ContextPolicy::Object => write!(f, "object"),
+            ContextPolicy::Type => write!(f, "type"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, clap::ValueEnum)]
+pub enum WidenTo {
+    Top,
+    Type,
+}
+
+impl fmt::Display for WidenTo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WidenTo::Top => write!(f, "top"),
+            WidenTo::Type => write!(f, "type"),
+        }
+    }
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum Command {
+    /// Work with points-to signatures
+    #[command(subcommand)]
+    Signatures(SignaturesCommand),
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum SignaturesCommand {
+    /// Print a signatures file with a guess for each declaration that lacks a
+    /// signature
+    Suggest {
+        /// LLVM bitcode module
+        module: PathBuf,
+
+        /// Existing points-to signatures, as for the main command
+        #[arg(short, long, value_delimiter = ',')]
+        signatures: Vec<String>,
+    },
+    /// Print signatures summarizing the exported functions of a library
+    Extract {
+        /// LLVM bitcode module of the library
+        module: PathBuf,
+
+        /// Points-to signatures for the library's dependencies, as for the
+        /// main command
+        #[arg(short, long, value_delimiter = ',')]
+        signatures: Vec<String>,
+    },
+}
+
 /// Pointer analysis for LLVM bitcode
 #[derive(Debug, clap::Parser)]
-#[command(author, version, about, long_about = None)]
+#[command(
+    author,
+    version,
+    about,
+    long_about = None,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
 pub struct Args {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     /// Check assertions
     #[arg(long, default_value_t = Check::Default)]
     pub check: Check,
 
+    /// Context sensitivity policy
+    #[arg(long, default_value_t = ContextPolicy::CallSite)]
+    pub context_policy: ContextPolicy,
+
     /// Context depth
     #[arg(long, default_value_t = 0)]
     pub contexts: u8,
 
+    /// Per-function context depths (JSON map from function name regexes to
+    /// depths), overriding --contexts
+    #[arg(long)]
+    pub context_depths: Option<PathBuf>,
+
     /// Debug
     #[arg(long)]
     pub debug: bool,
 
+    /// Field-sensitive analysis
+    #[arg(long)]
+    pub field_sensitive: bool,
+
+    /// Heap context depth (heap cloning)
+    #[arg(long, default_value_t = 0)]
+    pub heap_contexts: u8,
+
+    /// Only use contexts for functions that are cheap to analyze, according
+    /// to a context-insensitive pre-analysis
+    #[arg(long)]
+    pub introspective: bool,
+
+    /// Introspective mode: Maximum total points-to set size of a function
+    #[arg(long, default_value_t = 1000)]
+    pub introspective_max_points_to: usize,
+
+    /// Introspective mode: Maximum number of callers of a function
+    #[arg(long, default_value_t = 50)]
+    pub introspective_max_callers: usize,
+
     /// Quiet
     #[arg(long)]
     pub quiet: bool,
@@ -43,19 +146,52 @@ pub struct Args {
     #[arg(long)]
     pub metrics: bool,
 
+    /// Give the pointer returned by each call to a function without a
+    /// signature its own opaque allocation, which only becomes `Top` once it
+    /// escapes to unknown code
+    #[arg(long)]
+    pub opaque_externs: bool,
+
     /// LLVM bitcode module
-    #[arg()]
-    pub module: PathBuf,
+    #[arg(required = true)]
+    pub module: Option<PathBuf>,
 
-    /// Points-to signatures
-    #[arg(short, long)]
-    pub signatures: Option<PathBuf>,
+    /// Points-to signatures: paths to JSON files or built-in libraries
+    /// (builtin:libc, builtin:cxx, builtin:openssl, builtin:rust), comma-separated
+    #[arg(short, long, value_delimiter = ',')]
+    pub signatures: Vec<String>,
+
+    /// Report functions and globals matched by several signature regexes,
+    /// and regexes in signature files that match no declaration
+    #[arg(long)]
+    pub signature_diagnostics: bool,
+
+    /// Run the near-linear unification-based (Steensgaard-style) analysis
+    /// instead of the inclusion-based one. Much less precise, but fast.
+    #[arg(long)]
+    pub steensgaard: bool,
 
     /// Tracing
     #[arg(long)]
     pub tracing: bool,
 
+    /// Model functions without signatures as only returning unknown pointers,
+    /// rather than also storing to and calling whatever their arguments
+    /// point to
+    #[arg(long)]
+    pub unsound_externs: bool,
+
     /// Unification-based analysis
     #[arg(short, long)]
     pub unification: bool,
+
+    /// Collapse the points-to sets of loaded operands and stored-to
+    /// allocations that grow past this many allocations
+    #[arg(long)]
+    pub widen: Option<usize>,
+
+    /// What collapsed points-to sets are replaced by: Top, or a summary
+    /// allocation per pointer type
+    #[arg(long, default_value_t = WidenTo::Top)]
+    pub widen_to: WidenTo,
 }
@@ -0,0 +1,194 @@
+// SPDX-License-Identifier: BSD-3-Clause
+//! Calling contexts and the policies that construct them.
+//!
+//! The pointer analysis is parameterized by a [`ContextPolicy`], which
+//! determines the context of a callee given the context of its caller, the call
+//! site, and (for policies that use one) the receiver. The receiver of a call is
+//! the allocation pointed to by its first argument, which is `this` for C++
+//! methods.
+//!
+//! The depth of contexts (i.e., k) may vary by function, see
+//! `Options::function_contexts` and [`ContextDepths`].
+
+use std::{collections::HashMap, fmt::Display};
+
+use regex::RegexSet;
+
+use crate::{
+    alloc::{Alloc, AllocSite},
+    arc::{Arc, UArc},
+    klimited::KLimited,
+    llvm::{FunctionName, InstructionName, Module},
+};
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum ContextElem {
+    /// A call site, for [`CallSite`] sensitivity
+    CallSite(UArc<InstructionName>),
+    /// The allocation site of a receiver, for [`ObjectSensitive`] analysis
+    Object(AllocSite),
+    /// The function containing the allocation site of a receiver, for
+    /// [`TypeSensitive`] analysis
+    Type(UArc<FunctionName>),
+}
+
+impl Display for ContextElem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ContextElem::CallSite(i) => write!(f, "{}", i),
+            ContextElem::Object(s) => write!(f, "{}", s),
+            ContextElem::Type(t) => write!(f, "{}", t),
+        }
+    }
+}
+
+pub type Context = KLimited<ContextElem>;
+
+pub trait ContextPolicy: Sync {
+    /// Whether [`ContextPolicy::callee_context`] uses the receiver
+    fn uses_receiver(&self) -> bool;
+
+    /// Context of the callee at `call` when called in context `caller`.
+    /// `receiver` is `None` if the policy doesn't use receivers, the callee
+    /// isn't defined or doesn't take a pointer as its first argument, or the
+    /// first argument may point to nothing.
+    fn callee_context(
+        &self,
+        caller: &Context,
+        call: &UArc<InstructionName>,
+        receiver: Option<&Arc<Alloc>>,
+    ) -> Context;
+}
+
+/// k-callsite sensitivity: contexts are the k most recent call sites
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CallSite;
+
+impl ContextPolicy for CallSite {
+    fn uses_receiver(&self) -> bool {
+        false
+    }
+
+    fn callee_context(
+        &self,
+        caller: &Context,
+        call: &UArc<InstructionName>,
+        _receiver: Option<&Arc<Alloc>>,
+    ) -> Context {
+        caller.pushed(ContextElem::CallSite(call.clone()))
+    }
+}
+
+/// The context of a receiver's allocation, extended with `elem`. Calls without
+/// a receiver stay in the caller's context.
+fn receiver_context(
+    caller: &Context,
+    receiver: Option<&Arc<Alloc>>,
+    elem: impl FnOnce(AllocSite) -> ContextElem,
+) -> Context {
+    match receiver {
+        None => caller.clone(),
+        Some(r) => {
+            let heap_ctx = match Alloc::context(r) {
+                Some(ctx) => ctx.truncated(caller.limit()),
+                None => KLimited::new(caller.limit(), vec![]),
+            };
+            heap_ctx.pushed(elem(Alloc::site(r)))
+        }
+    }
+}
+
+/// k-object sensitivity: contexts are the allocation site of the receiver,
+/// followed by the context of that allocation (see `Options::heap_contexts`).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ObjectSensitive;
+
+impl ContextPolicy for ObjectSensitive {
+    fn uses_receiver(&self) -> bool {
+        true
+    }
+
+    fn callee_context(
+        &self,
+        caller: &Context,
+        _call: &UArc<InstructionName>,
+        receiver: Option<&Arc<Alloc>>,
+    ) -> Context {
+        receiver_context(caller, receiver, ContextElem::Object)
+    }
+}
+
+/// Type sensitivity: like [`ObjectSensitive`], but allocation sites are
+/// coarsened to the function that contains them. This is the analogue of the
+/// class containing the allocation site in object-oriented languages.
+/// Allocations without a containing function (e.g., globals) are not
+/// coarsened.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TypeSensitive;
+
+impl ContextPolicy for TypeSensitive {
+    fn uses_receiver(&self) -> bool {
+        true
+    }
+
+    fn callee_context(
+        &self,
+        caller: &Context,
+        _call: &UArc<InstructionName>,
+        receiver: Option<&Arc<Alloc>>,
+    ) -> Context {
+        receiver_context(caller, receiver, |site| match site.function() {
+            Some(f) => ContextElem::Type(f.clone()),
+            None => ContextElem::Object(site),
+        })
+    }
+}
+
+/// Per-function context depths, keyed by regexes on function names (like
+/// [`crate::signatures::Signatures`])
+#[derive(Debug)]
+pub struct ContextDepths {
+    depths: HashMap<String, u8>,
+    regexes: Vec<String>,
+    set: RegexSet,
+}
+
+impl Default for ContextDepths {
+    fn default() -> Self {
+        ContextDepths {
+            depths: HashMap::new(),
+            regexes: Vec::new(),
+            set: RegexSet::new::<[String; 0], _>([]).unwrap(),
+        }
+    }
+}
+
+impl ContextDepths {
+    pub fn new(depths: HashMap<String, u8>) -> Result<Self, regex::Error> {
+        let regexes = depths.keys().map(|s| s.to_string()).collect();
+        let set = RegexSet::new(depths.keys())?;
+        Ok(ContextDepths {
+            depths,
+            regexes,
+            set,
+        })
+    }
+
+    /// If several regexes match, the greatest depth wins.
+    pub fn depth_for(&self, func: &str) -> Option<u8> {
+        self.set
+            .matches(func)
+            .into_iter()
+            .map(|m| self.depths[&self.regexes[m]])
+            .max()
+    }
+
+    /// Depths for the functions defined in this module
+    pub fn for_module(&self, module: &Module) -> HashMap<UArc<FunctionName>, u8> {
+        module
+            .functions
+            .keys()
+            .filter_map(|f| Some((f.clone(), self.depth_for(f.get())?)))
+            .collect()
+    }
+}
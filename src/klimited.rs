@@ -19,6 +19,11 @@ impl<T> KLimited<T> {
         KLimited { k, elems }
     }
 
+    /// The maximum number of elements
+    pub fn limit(&self) -> usize {
+        self.k
+    }
+
     pub fn push(&mut self, t: T) {
         if self.elems.len() >= self.k {
             self.elems.pop_back();
@@ -37,6 +42,17 @@ impl<T> KLimited<T> {
         new
     }
 
+    /// Keep only the `k` most recently pushed elements
+    pub fn truncated(&self, k: usize) -> Self
+    where
+        T: Clone,
+    {
+        KLimited {
+            k,
+            elems: self.elems.iter().take(k).cloned().collect(),
+        }
+    }
+
     // TODO
     #[allow(clippy::should_implement_trait)]
     pub fn into_iter(self) -> impl Iterator<Item = T> {
@@ -5,6 +5,7 @@ pub mod analysis;
 pub use analysis::*;
 pub mod arc;
 pub use arc::*;
+pub mod context;
 pub mod hash;
 mod klimited;
 pub use klimited::*;
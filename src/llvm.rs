@@ -26,6 +26,8 @@ pub use name::*;
 mod operand;
 pub use operand::*;
 pub mod instruction;
+mod layout;
+pub use layout::*;
 pub mod terminator;
 pub use terminator::*;
 
@@ -43,6 +45,7 @@ impl Instruction {
         globals: &HashMap<&str, Arc<Constant>>,
         locals: &HashMap<&Name, Arc<Operand>>,
         instructions: &HashMap<&Name, UArc<InstructionName>>,
+        layout: &Layout,
         m: &'module llvm_ir::Module,
         i: &'module llvm_ir::Instruction,
         f_name: &UArc<FunctionName>,
@@ -62,12 +65,7 @@ impl Instruction {
                 }
             },
             opcode: PreHashed::new(Opcode::from_instruction(
-                &m.data_layout,
-                &m.types,
-                operands,
-                globals,
-                locals,
-                i,
+                layout, &m.types, operands, globals, locals, i,
             )?),
             ty: i.get_type(&m.types),
         })
@@ -88,6 +86,7 @@ impl Terminator {
         globals: &HashMap<&str, Arc<Constant>>,
         locals: &HashMap<&Name, Arc<Operand>>,
         instructions: &HashMap<&Name, UArc<InstructionName>>,
+        landing_pads: &HashMap<&Name, Arc<Operand>>,
         m: &'module llvm_ir::Module,
         b: &'module llvm_ir::BasicBlock,
         i: &'module llvm_ir::Terminator,
@@ -107,7 +106,11 @@ impl Terminator {
                 }
             },
             opcode: PreHashed::new(TerminatorOpcode::from_terminator(
-                operands, globals, locals, i,
+                operands,
+                globals,
+                locals,
+                landing_pads,
+                i,
             )?),
             ty: i.get_type(&m.types),
         })
@@ -128,6 +131,8 @@ impl Block {
         globals: &HashMap<&str, Arc<Constant>>,
         locals: &HashMap<&Name, Arc<Operand>>,
         instructions: &HashMap<&Name, UArc<InstructionName>>,
+        landing_pads: &HashMap<&Name, Arc<Operand>>,
+        layout: &Layout,
         m: &'module llvm_ir::Module,
         b: &'module llvm_ir::BasicBlock,
         f_name: &UArc<FunctionName>,
@@ -140,6 +145,7 @@ impl Block {
                 globals,
                 locals,
                 instructions,
+                layout,
                 m,
                 i,
                 f_name,
@@ -152,6 +158,7 @@ impl Block {
             globals,
             locals,
             instructions,
+            landing_pads,
             m,
             b,
             &b.term,
@@ -168,7 +175,11 @@ impl Block {
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub struct Function {
+    /// Whether the function can be called from outside the module, i.e.,
+    /// doesn't have private, internal, or `available_externally` linkage
+    pub exported: bool,
     pub parameters: Vec<Arc<Operand>>,
+    pub parameter_types: Vec<llvm_ir::TypeRef>,
     pub blocks: Vec<Block>,
     pub return_type: llvm_ir::TypeRef,
 }
@@ -177,6 +188,7 @@ impl Function {
     pub(crate) fn new<'module>(
         operands: &mut HashMap<Arc<Operand>, &'module llvm_ir::Operand>,
         globals: &HashMap<&str, Arc<Constant>>,
+        layout: &Layout,
         m: &'module llvm_ir::Module,
         f: &'module llvm_ir::Function,
         name: &UArc<FunctionName>,
@@ -212,12 +224,20 @@ impl Function {
                     instructions.insert(n, inst_name.clone());
 
                     // NOTE[pass-thru] TODO: See #32.
+                    //
+                    // Only `getelementptr`s with all-zero indices are
+                    // collapsed, as others may select a field (see
+                    // `Options::field_sensitive`).
                     let get_local = |op: &llvm_ir::Operand| match op {
                         llvm_ir::Operand::LocalOperand { name, .. } => locals.get(name),
                         _ => None,
                     };
                     if let Some(op) = match &instr {
-                        llvm_ir::Instruction::GetElementPtr(i) => get_local(&i.address),
+                        llvm_ir::Instruction::GetElementPtr(i)
+                            if instruction::GetElementPtr::is_zero(i) =>
+                        {
+                            get_local(&i.address)
+                        }
                         llvm_ir::Instruction::PtrToInt(i) => get_local(&i.operand),
                         llvm_ir::Instruction::IntToPtr(i) => get_local(&i.operand),
                         llvm_ir::Instruction::BitCast(i) => get_local(&i.operand),
@@ -255,6 +275,21 @@ impl Function {
             block_names.push(block_name);
         }
 
+        // The `landingpad` instructions that begin unwind destinations, which
+        // may follow `phi`s
+        let mut landing_pads = HashMap::<&Name, Arc<Operand>>::new();
+        for b in &f.basic_blocks {
+            let first = b
+                .instrs
+                .iter()
+                .find(|i| !matches!(i, llvm_ir::Instruction::Phi(_)));
+            if let Some(llvm_ir::Instruction::LandingPad(lp)) = first {
+                if let Some(op) = locals.get(&lp.dest) {
+                    landing_pads.insert(&b.name, op.clone());
+                }
+            }
+        }
+
         // This has to happen in a subsequent loop because LLVM sometimes
         // references variables before their definitions...
         let mut blocks = Vec::with_capacity(f.basic_blocks.len());
@@ -264,6 +299,8 @@ impl Function {
                 globals,
                 &locals,
                 &instructions,
+                &landing_pads,
+                layout,
                 m,
                 b,
                 name,
@@ -272,7 +309,14 @@ impl Function {
         }
 
         Ok(Function {
+            exported: !matches!(
+                f.linkage,
+                llvm_ir::module::Linkage::Private
+                    | llvm_ir::module::Linkage::Internal
+                    | llvm_ir::module::Linkage::AvailableExternally
+            ),
             parameters,
+            parameter_types: f.parameters.iter().map(|p| p.ty.clone()).collect(),
             blocks,
             return_type: f.return_type.clone(),
         })
@@ -283,6 +327,7 @@ impl Function {
 pub struct Decl {
     pub parameters: Vec<llvm_ir::TypeRef>,
     pub return_type: llvm_ir::TypeRef,
+    pub is_var_arg: bool,
 }
 
 impl Decl {
@@ -293,6 +338,7 @@ impl Decl {
         Ok(Decl {
             parameters: d.parameters.iter().map(|p| p.ty.clone()).collect(),
             return_type: d.return_type.clone(),
+            is_var_arg: d.is_var_arg,
         })
     }
 
@@ -332,20 +378,29 @@ impl Global {
         })
     }
 
-    /// In bytes
-    pub(crate) fn size(&self) -> Option<u64> {
-        // TODO: Properly support type sizes
-        // https://github.com/cdisselkoen/llvm-ir/issues/31
-        if let llvm_ir::Type::PointerType { pointee_type, .. } = &*self.ty {
-            // Globals always have pointer types at the top level, so look
-            // inside...
-            if let llvm_ir::Type::PointerType { .. } = &**pointee_type {
-                Some(8)
-            } else {
-                None
+    /// The non-aggregate constants in the initializer, along with their byte
+    /// offsets from the start of the global.
+    pub(crate) fn initializer_offsets(&self, layout: &Layout) -> Vec<(u64, &Arc<Constant>)> {
+        match (&self.initializer, &*self.ty) {
+            (Some(init), llvm_ir::Type::PointerType { pointee_type, .. }) => {
+                layout.initializer_offsets(pointee_type, init)
+            }
+            (Some(init), _) => vec![(0, init)],
+            (None, _) => Vec::new(),
+        }
+    }
+
+    /// In bytes, see [`Layout::size_of`]. Unknown for declarations, whose
+    /// definitions may be bigger (e.g., `extern char buf[]` is declared with
+    /// type `[0 x i8]`), and for zero-sized types.
+    pub fn size(&self, layout: &Layout) -> Option<u64> {
+        self.initializer.as_ref()?;
+        // Globals always have pointer types at the top level, so look inside
+        match &*self.ty {
+            llvm_ir::Type::PointerType { pointee_type, .. } => {
+                layout.size_of(pointee_type).filter(|s| *s != 0)
             }
-        } else {
-            None
+            _ => None,
         }
     }
 }
@@ -355,9 +410,39 @@ pub struct Module {
     pub decls: HashMap<UArc<FunctionName>, Decl>,
     pub functions: HashMap<UArc<FunctionName>, Function>,
     pub globals: HashMap<Arc<GlobalName>, Global>,
+    /// Global aliases, mapped to their aliasees
+    pub aliases: HashMap<Arc<GlobalName>, Arc<Constant>>,
+    pub(crate) layout: Layout,
 }
 
 impl Module {
+    /// Sizes and field offsets of types, according to the module's data
+    /// layout
+    pub fn layout(&self) -> &Layout {
+        &self.layout
+    }
+
+    /// Upper bound on the size of the objects in this module that can be
+    /// statically determined, i.e., of named structs and globals
+    pub(crate) fn max_object_size(&self) -> u64 {
+        let globals = self.globals.values().filter_map(|g| g.size(&self.layout));
+        globals
+            .chain(std::iter::once(self.layout.max_struct_size()))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Whether this function is defined and its first parameter is a pointer,
+    /// i.e., could be a receiver (see [`crate::context`]). Declarations have
+    /// no contexts to distinguish.
+    pub(crate) fn has_receiver(&self, f: &UArc<FunctionName>) -> bool {
+        let first = self
+            .functions
+            .get(f)
+            .and_then(|func| func.parameter_types.first());
+        matches!(first.map(|t| &**t), Some(llvm_ir::Type::PointerType { .. }))
+    }
+
     /// Collect names of functions, global variables into Arcs so they can
     /// be shared
     fn global_names(m: &llvm_ir::Module) -> HashMap<&str, Arc<Constant>> {
@@ -406,6 +491,7 @@ impl Module {
         operands: &mut HashMap<Arc<Operand>, &'module llvm_ir::Operand>,
     ) -> Result<Self, Error> {
         let global_names = Self::global_names(m);
+        let layout = Layout::new(&m.data_layout, &m.types);
         let mut functions: HashMap<UArc<FunctionName>, Function> =
             HashMap::with_capacity(m.functions.len());
         for f in &m.functions {
@@ -417,7 +503,7 @@ impl Module {
                 },
                 _ => unreachable!("Whoops"),
             };
-            let func = Function::new(operands, &global_names, m, f, &name)?;
+            let func = Function::new(operands, &global_names, &layout, m, f, &name)?;
             functions.insert(name, func);
         }
 
@@ -437,6 +523,22 @@ impl Module {
             );
         }
 
+        let mut aliases: HashMap<Arc<GlobalName>, Arc<Constant>> =
+            HashMap::with_capacity(m.global_aliases.len());
+        for a in &m.global_aliases {
+            aliases.insert(
+                // This is a hack, but... gotta not duplicate those strings!
+                match global_names.get::<str>(a.name.as_ref()) {
+                    Some(constant) => match &**constant {
+                        Constant::Global(g) => g.clone(),
+                        _ => unreachable!("Whoops"),
+                    },
+                    _ => unreachable!("Whoops"),
+                },
+                Constant::create(&global_names, &a.aliasee)?,
+            );
+        }
+
         let mut decls: HashMap<UArc<FunctionName>, Decl> =
             HashMap::with_capacity(m.func_declarations.len());
         for d in &m.func_declarations {
@@ -457,6 +559,8 @@ impl Module {
             decls,
             functions,
             globals,
+            aliases,
+            layout,
         })
     }
 }
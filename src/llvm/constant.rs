@@ -4,6 +4,7 @@ use std::{collections::HashMap, fmt::Display};
 use crate::arc::{Arc, UArc};
 
 use super::error::Error;
+use super::layout::Layout;
 use super::name::{FunctionName, GlobalName};
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
@@ -20,9 +21,102 @@ impl BitCast {
     }
 }
 
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct ExtractElement {
+    vector: Arc<Constant>,
+}
+
+impl ExtractElement {
+    pub fn from_extractelement(
+        globals: &HashMap<&str, Arc<Constant>>,
+        e: &llvm_ir::constant::ExtractElement,
+    ) -> Result<Self, Error> {
+        Constant::create(globals, &e.vector).map(|c| ExtractElement { vector: c })
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct InsertElement {
+    vector: Arc<Constant>,
+    element: Arc<Constant>,
+}
+
+impl InsertElement {
+    pub fn from_insertelement(
+        globals: &HashMap<&str, Arc<Constant>>,
+        i: &llvm_ir::constant::InsertElement,
+    ) -> Result<Self, Error> {
+        Ok(InsertElement {
+            vector: Constant::create(globals, &i.vector)?,
+            element: Constant::create(globals, &i.element)?,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct ShuffleVector {
+    operand0: Arc<Constant>,
+    operand1: Arc<Constant>,
+}
+
+impl ShuffleVector {
+    pub fn from_shufflevector(
+        globals: &HashMap<&str, Arc<Constant>>,
+        s: &llvm_ir::constant::ShuffleVector,
+    ) -> Result<Self, Error> {
+        Ok(ShuffleVector {
+            operand0: Constant::create(globals, &s.operand0)?,
+            operand1: Constant::create(globals, &s.operand1)?,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct ExtractValue {
+    aggregate: Arc<Constant>,
+    indices: Vec<u32>,
+}
+
+impl ExtractValue {
+    pub fn from_extractvalue(
+        globals: &HashMap<&str, Arc<Constant>>,
+        e: &llvm_ir::constant::ExtractValue,
+    ) -> Result<Self, Error> {
+        Constant::create(globals, &e.aggregate).map(|c| ExtractValue {
+            aggregate: c,
+            indices: e.indices.clone(),
+        })
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct InsertValue {
+    aggregate: Arc<Constant>,
+    element: Arc<Constant>,
+    indices: Vec<u32>,
+}
+
+impl InsertValue {
+    pub fn from_insertvalue(
+        globals: &HashMap<&str, Arc<Constant>>,
+        i: &llvm_ir::constant::InsertValue,
+    ) -> Result<Self, Error> {
+        Ok(InsertValue {
+            aggregate: Constant::create(globals, &i.aggregate)?,
+            element: Constant::create(globals, &i.element)?,
+            indices: i.indices.clone(),
+        })
+    }
+}
+
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub struct GetElementPtr {
     pub(crate) pointer: Arc<Constant>,
+    /// Type that the indices step through, if it could be determined from the
+    /// pointer operand
+    source: Option<llvm_ir::TypeRef>,
+    /// `None` for non-constant-integer indices
+    indices: Vec<Option<i64>>,
 }
 
 impl GetElementPtr {
@@ -30,7 +124,45 @@ impl GetElementPtr {
         globals: &HashMap<&str, Arc<Constant>>,
         gep: &llvm_ir::constant::GetElementPtr,
     ) -> Result<Self, Error> {
-        Constant::create(globals, &gep.address).map(|c| GetElementPtr { pointer: c })
+        let pointee = |ty: &llvm_ir::TypeRef| match &**ty {
+            llvm_ir::Type::PointerType { pointee_type, .. } => Some(pointee_type.clone()),
+            _ => None,
+        };
+        let source = match &*gep.address {
+            llvm_ir::Constant::GlobalReference { ty, .. } => Some(ty.clone()),
+            llvm_ir::Constant::BitCast(b) => pointee(&b.to_type),
+            llvm_ir::Constant::IntToPtr(i) => pointee(&i.to_type),
+            _ => None,
+        };
+        let indices = gep
+            .indices
+            .iter()
+            .map(|c| match &**c {
+                llvm_ir::Constant::Int { bits, value } => Some(sign_extend(*bits, *value)),
+                _ => None,
+            })
+            .collect();
+        Constant::create(globals, &gep.address).map(|c| GetElementPtr {
+            pointer: c,
+            source,
+            indices,
+        })
+    }
+
+    /// Byte offset of the field selected by this `getelementptr`, if it only
+    /// selects a field (i.e., its first index is zero and the rest are
+    /// constants).
+    pub(crate) fn field_offset(&self, layout: &Layout) -> Option<u64> {
+        layout.field_gep_offset(self.source.as_ref()?, &self.indices)
+    }
+}
+
+pub(crate) fn sign_extend(bits: u32, value: u64) -> i64 {
+    if bits == 0 || bits >= 64 {
+        value as i64
+    } else {
+        let shift = 64 - bits;
+        ((value << shift) as i64) >> shift
     }
 }
 
@@ -64,7 +196,7 @@ impl PtrToInt {
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub struct Struct {
-    fields: Vec<Arc<Constant>>,
+    pub(crate) fields: Vec<Arc<Constant>>,
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
@@ -92,11 +224,11 @@ pub enum Constant {
     FMul,
     FDiv,
     FRem,
-    ExtractElement,
-    InsertElement,
-    ShuffleVector,
-    ExtractValue,
-    InsertValue,
+    ExtractElement(ExtractElement),
+    InsertElement(InsertElement),
+    ShuffleVector(ShuffleVector),
+    ExtractValue(ExtractValue),
+    InsertValue(InsertValue),
     GetElementPtr(GetElementPtr),
     Trunc,
     ZExt,
@@ -118,6 +250,7 @@ pub enum Constant {
     Array(Vec<Arc<Constant>>),
     Struct(Struct),
     Undef,
+    Vector(Vec<Arc<Constant>>),
     //
     Other,
 }
@@ -159,11 +292,21 @@ impl Constant {
             llvm_ir::Constant::FMul(_) => Ok(Arc::new(Constant::FMul)),
             llvm_ir::Constant::FDiv(_) => Ok(Arc::new(Constant::FDiv)),
             llvm_ir::Constant::FRem(_) => Ok(Arc::new(Constant::FRem)),
-            llvm_ir::Constant::ExtractElement(_) => Ok(Arc::new(Constant::ExtractElement)),
-            llvm_ir::Constant::InsertElement(_) => Ok(Arc::new(Constant::InsertElement)),
-            llvm_ir::Constant::ShuffleVector(_) => Ok(Arc::new(Constant::ShuffleVector)),
-            llvm_ir::Constant::ExtractValue(_) => Ok(Arc::new(Constant::ExtractValue)),
-            llvm_ir::Constant::InsertValue(_) => Ok(Arc::new(Constant::InsertValue)),
+            llvm_ir::Constant::ExtractElement(e) => ExtractElement::from_extractelement(globals, e)
+                .map(Constant::ExtractElement)
+                .map(Arc::new),
+            llvm_ir::Constant::InsertElement(i) => InsertElement::from_insertelement(globals, i)
+                .map(Constant::InsertElement)
+                .map(Arc::new),
+            llvm_ir::Constant::ShuffleVector(s) => ShuffleVector::from_shufflevector(globals, s)
+                .map(Constant::ShuffleVector)
+                .map(Arc::new),
+            llvm_ir::Constant::ExtractValue(e) => ExtractValue::from_extractvalue(globals, e)
+                .map(Constant::ExtractValue)
+                .map(Arc::new),
+            llvm_ir::Constant::InsertValue(i) => InsertValue::from_insertvalue(globals, i)
+                .map(Constant::InsertValue)
+                .map(Arc::new),
             llvm_ir::Constant::GetElementPtr(g) => GetElementPtr::from_getelementptr(globals, g)
                 .map(Constant::GetElementPtr)
                 .map(Arc::new),
@@ -205,46 +348,122 @@ impl Constant {
                 Ok(Arc::new(Constant::Struct(Struct { fields })))
             }
             llvm_ir::Constant::Undef(_) => Ok(Arc::new(Constant::Undef)),
+            llvm_ir::Constant::Vector(elements) => {
+                let mut es = Vec::with_capacity(elements.len());
+                for e in elements {
+                    es.push(Constant::create(globals, e)?);
+                }
+                Ok(Arc::new(Constant::Vector(es)))
+            }
             _ => Ok(Arc::new(Constant::Other)),
             // c => panic!("Unhandled constant: {c}")
             // llvm_ir::Constant::Float(_) => todo!(),
             // llvm_ir::Constant::AggregateZero(_) => todo!(),
-            // llvm_ir::Constant::Vector(_) => todo!(),
             // llvm_ir::Constant::Poison(_) => todo!(),
             // llvm_ir::Constant::BlockAddress => todo!(),
             // llvm_ir::Constant::TokenNone => todo!(),
         }
     }
 
+    /// Like [`Constant::pointers`], but also computes the byte offset of each
+    /// pointer into the pointed-to object, or `None` if it isn't known (see
+    /// [`GetElementPtr::field_offset`]).
+    pub(crate) fn field_pointers(&self, layout: &Layout) -> Vec<(Constant, Option<u64>)> {
+        match self {
+            Constant::GetElementPtr(g) => {
+                let offset = g.field_offset(layout);
+                g.pointer
+                    .field_pointers(layout)
+                    .into_iter()
+                    .map(|(c, o)| (c, o.zip(offset).map(|(o, offset)| o + offset)))
+                    .collect()
+            }
+            Constant::PtrToInt(c) => c.pointer.field_pointers(layout),
+            Constant::IntToPtr(c) => c.int.field_pointers(layout),
+            Constant::BitCast(b) => b.pointer.field_pointers(layout),
+            Constant::Array(v) | Constant::Vector(v) => {
+                v.iter().flat_map(|c| c.field_pointers(layout)).collect()
+            }
+            Constant::Struct(Struct { fields }) => fields
+                .iter()
+                .flat_map(|c| c.field_pointers(layout))
+                .collect(),
+            _ => self.pointers().into_iter().map(|c| (c, Some(0))).collect(),
+        }
+    }
+
+    /// Like [`Constant::pointers`], but only for the element of this aggregate
+    /// at the given (`extractvalue`) indices, if it can be determined.
+    pub(crate) fn element_pointers(&self, indices: &[u32]) -> Vec<Constant> {
+        let Some((first, rest)) = indices.split_first() else {
+            return self.pointers();
+        };
+        match self {
+            Constant::Array(v) => match v.get(*first as usize) {
+                Some(e) => e.element_pointers(rest),
+                None => self.pointers(),
+            },
+            Constant::Struct(Struct { fields }) => match fields.get(*first as usize) {
+                Some(e) => e.element_pointers(rest),
+                None => self.pointers(),
+            },
+            Constant::InsertValue(i) => {
+                if let Some(inner) = indices.strip_prefix(i.indices.as_slice()) {
+                    // Within the inserted element
+                    i.element.element_pointers(inner)
+                } else if i.indices.starts_with(indices) {
+                    // Contains the inserted element
+                    self.pointers()
+                } else {
+                    i.aggregate.element_pointers(indices)
+                }
+            }
+            _ => self.pointers(),
+        }
+    }
+
     pub fn pointers(&self) -> Vec<Constant> {
         match self {
             Constant::Function(_) => vec![self.clone()],
             Constant::Global(_) => vec![self.clone()],
             Constant::Int { .. } => vec![self.clone()],
             Constant::Null => vec![self.clone()],
-            Constant::Add => vec![],            // TODO
-            Constant::Sub => vec![],            // TODO
-            Constant::Mul => vec![],            // TODO
-            Constant::UDiv => vec![],           // TODO
-            Constant::SDiv => vec![],           // TODO
-            Constant::URem => vec![],           // TODO
-            Constant::SRem => vec![],           // TODO
-            Constant::And => vec![],            // TODO
-            Constant::Or => vec![],             // TODO
-            Constant::Xor => vec![],            // TODO
-            Constant::Shl => vec![],            // TODO
-            Constant::LShr => vec![],           // TODO
-            Constant::AShr => vec![],           // TODO
-            Constant::FAdd => vec![],           // TODO
-            Constant::FSub => vec![],           // TODO
-            Constant::FMul => vec![],           // TODO
-            Constant::FDiv => vec![],           // TODO
-            Constant::FRem => vec![],           // TODO
-            Constant::ExtractElement => vec![], // TODO
-            Constant::InsertElement => vec![],  // TODO
-            Constant::ShuffleVector => vec![],  // TODO
-            Constant::ExtractValue => vec![],   // TODO
-            Constant::InsertValue => vec![],    // TODO
+            Constant::Add => vec![],  // TODO
+            Constant::Sub => vec![],  // TODO
+            Constant::Mul => vec![],  // TODO
+            Constant::UDiv => vec![], // TODO
+            Constant::SDiv => vec![], // TODO
+            Constant::URem => vec![], // TODO
+            Constant::SRem => vec![], // TODO
+            Constant::And => vec![],  // TODO
+            Constant::Or => vec![],   // TODO
+            Constant::Xor => vec![],  // TODO
+            Constant::Shl => vec![],  // TODO
+            Constant::LShr => vec![], // TODO
+            Constant::AShr => vec![], // TODO
+            Constant::FAdd => vec![], // TODO
+            Constant::FSub => vec![], // TODO
+            Constant::FMul => vec![], // TODO
+            Constant::FDiv => vec![], // TODO
+            Constant::FRem => vec![], // TODO
+            // Elements of vectors are not distinguished
+            Constant::ExtractElement(e) => e.vector.pointers(),
+            Constant::InsertElement(i) => {
+                let mut ps = i.vector.pointers();
+                ps.extend(i.element.pointers());
+                ps
+            }
+            Constant::ShuffleVector(s) => {
+                let mut ps = s.operand0.pointers();
+                ps.extend(s.operand1.pointers());
+                ps
+            }
+            Constant::ExtractValue(e) => e.aggregate.element_pointers(&e.indices),
+            Constant::InsertValue(i) => {
+                let mut ps = i.aggregate.pointers();
+                ps.extend(i.element.pointers());
+                ps
+            }
             Constant::GetElementPtr(g) => g.pointer.pointers(),
             Constant::Trunc => vec![],   // TODO
             Constant::ZExt => vec![],    // TODO
@@ -268,6 +487,7 @@ impl Constant {
                 fields.iter().flat_map(|c| c.pointers()).collect()
             }
             Constant::Undef => vec![self.clone()],
+            Constant::Vector(v) => v.iter().flat_map(|c| c.pointers()).collect(),
             //
             Constant::Other => vec![],
         }
@@ -303,11 +523,17 @@ impl Display for Constant {
                 Constant::FMul => "FMul".to_string(),
                 Constant::FDiv => "FDiv".to_string(),
                 Constant::FRem => "FRem".to_string(),
-                Constant::ExtractElement => "ExtractElement".to_string(),
-                Constant::InsertElement => "InsertElement".to_string(),
-                Constant::ShuffleVector => "ShuffleVector".to_string(),
-                Constant::ExtractValue => "ExtractValue".to_string(),
-                Constant::InsertValue => "InsertValue".to_string(),
+                Constant::ExtractElement(e) => format!("extractelement({})", e.vector),
+                Constant::InsertElement(i) => {
+                    format!("insertelement({}, {})", i.vector, i.element)
+                }
+                Constant::ShuffleVector(s) => {
+                    format!("shufflevector({}, {})", s.operand0, s.operand1)
+                }
+                Constant::ExtractValue(e) => format!("extractvalue({})", e.aggregate),
+                Constant::InsertValue(i) => {
+                    format!("insertvalue({}, {})", i.aggregate, i.element)
+                }
                 Constant::GetElementPtr(g) => format!("getelementptr({})", g.pointer),
                 Constant::Trunc => "Trunc".to_string(),
                 Constant::ZExt => "ZExt".to_string(),
@@ -342,6 +568,13 @@ impl Display for Constant {
                         .join(", ")
                 ),
                 Constant::Undef => "undef".to_string(),
+                Constant::Vector(v) => format!(
+                    "< {} >",
+                    v.iter()
+                        .map(|c| format!("{}", c))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
                 //
                 Constant::Other => "<some constant>".to_string(),
             }
@@ -2,12 +2,16 @@
 use std::collections::HashMap;
 
 use either::Either;
-use llvm_ir::{module::DataLayout, types::Types, Name};
+use llvm_ir::{
+    types::{Typed, Types},
+    Name,
+};
 
 use crate::arc::Arc;
 
-use super::constant::Constant;
+use super::constant::{sign_extend, Constant};
 use super::error::Error;
+use super::layout::Layout;
 use super::operand::{Callee, Operand};
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
@@ -33,19 +37,51 @@ impl Add {
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub struct Alloca {
     pub(crate) num_elements: Arc<Operand>,
+    /// Whether the allocated type could hold a pointer
+    pub(crate) may_hold_pointer: bool,
+    /// In bytes, if the number of elements is constant
+    pub(crate) size: Option<u64>,
 }
 
 impl Alloca {
     pub(crate) fn from_alloca<'module>(
-        _dl: &'module DataLayout,
+        layout: &Layout,
         _types: &'module Types,
         operands: &mut HashMap<Arc<Operand>, &'module llvm_ir::Operand>,
         globals: &HashMap<&str, Arc<Constant>>,
         locals: &HashMap<&Name, Arc<Operand>>,
         alloca: &'module llvm_ir::instruction::Alloca,
     ) -> Result<Self, Error> {
+        let num_elements = Operand::new(operands, globals, locals, &alloca.num_elements)?;
+        let size = layout
+            .size_of(&alloca.allocated_type)
+            .zip(num_elements.constant_int())
+            .and_then(|(size, n)| size.checked_mul(n));
         Ok(Alloca {
-            num_elements: Operand::new(operands, globals, locals, &alloca.num_elements)?,
+            num_elements,
+            may_hold_pointer: layout.may_hold_pointer(&alloca.allocated_type),
+            size,
+        })
+    }
+}
+
+/// `atomicrmw`, i.e., a load and a store
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct AtomicRMW {
+    pub(crate) pointer: Arc<Operand>,
+    pub(crate) value: Arc<Operand>,
+}
+
+impl AtomicRMW {
+    pub(crate) fn from_atomicrmw<'module>(
+        operands: &mut HashMap<Arc<Operand>, &'module llvm_ir::Operand>,
+        globals: &HashMap<&str, Arc<Constant>>,
+        locals: &HashMap<&Name, Arc<Operand>>,
+        i: &'module llvm_ir::instruction::AtomicRMW,
+    ) -> Result<Self, Error> {
+        Ok(AtomicRMW {
+            pointer: Operand::new(operands, globals, locals, &i.address)?,
+            value: Operand::new(operands, globals, locals, &i.value)?,
         })
     }
 }
@@ -106,22 +142,161 @@ impl Call {
     }
 }
 
+/// `cmpxchg`, i.e., a load and a (conditional) store. The result is a pair of
+/// the loaded value and a success flag.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct CmpXchg {
+    pub(crate) pointer: Arc<Operand>,
+    pub(crate) expected: Arc<Operand>,
+    pub(crate) replacement: Arc<Operand>,
+    /// Type of the replacement (and loaded) value
+    pub(crate) ty: llvm_ir::TypeRef,
+}
+
+impl CmpXchg {
+    pub(crate) fn from_cmpxchg<'module>(
+        types: &Types,
+        operands: &mut HashMap<Arc<Operand>, &'module llvm_ir::Operand>,
+        globals: &HashMap<&str, Arc<Constant>>,
+        locals: &HashMap<&Name, Arc<Operand>>,
+        i: &'module llvm_ir::instruction::CmpXchg,
+    ) -> Result<Self, Error> {
+        Ok(CmpXchg {
+            pointer: Operand::new(operands, globals, locals, &i.address)?,
+            expected: Operand::new(operands, globals, locals, &i.expected)?,
+            replacement: Operand::new(operands, globals, locals, &i.replacement)?,
+            ty: i.replacement.get_type(types),
+        })
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct ExtractElement {
+    pub(crate) vector: Arc<Operand>,
+}
+
+impl ExtractElement {
+    pub(crate) fn from_extractelement<'module>(
+        operands: &mut HashMap<Arc<Operand>, &'module llvm_ir::Operand>,
+        globals: &HashMap<&str, Arc<Constant>>,
+        locals: &HashMap<&Name, Arc<Operand>>,
+        i: &'module llvm_ir::instruction::ExtractElement,
+    ) -> Result<Self, Error> {
+        Ok(ExtractElement {
+            vector: Operand::new(operands, globals, locals, &i.vector)?,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct ExtractValue {
+    pub(crate) aggregate: Arc<Operand>,
+    pub(crate) indices: Vec<u32>,
+}
+
+impl ExtractValue {
+    pub(crate) fn from_extractvalue<'module>(
+        operands: &mut HashMap<Arc<Operand>, &'module llvm_ir::Operand>,
+        globals: &HashMap<&str, Arc<Constant>>,
+        locals: &HashMap<&Name, Arc<Operand>>,
+        i: &'module llvm_ir::instruction::ExtractValue,
+    ) -> Result<Self, Error> {
+        Ok(ExtractValue {
+            aggregate: Operand::new(operands, globals, locals, &i.aggregate)?,
+            indices: i.indices.clone(),
+        })
+    }
+}
+
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub struct GetElementPtr {
     pub(crate) pointer: Arc<Operand>,
+    /// Byte offset of the field selected by this instruction, if it only
+    /// selects a field (i.e., its first index is zero and the rest are
+    /// constants).
+    pub(crate) field_offset: Option<u64>,
+    /// Whether all indices are zero, see NOTE[pass-thru].
+    pub(crate) zero: bool,
+}
+
+fn constant_index(op: &llvm_ir::Operand) -> Option<i64> {
+    match op {
+        llvm_ir::Operand::ConstantOperand(c) => match &**c {
+            llvm_ir::Constant::Int { bits, value } => Some(sign_extend(*bits, *value)),
+            _ => None,
+        },
+        _ => None,
+    }
 }
 
 impl GetElementPtr {
+    /// Whether all indices are zero, so that the result is the same pointer
+    /// as the operand.
+    pub(crate) fn is_zero(gep: &llvm_ir::instruction::GetElementPtr) -> bool {
+        gep.indices.iter().all(|i| constant_index(i) == Some(0))
+    }
+
     pub(crate) fn from_gep<'module>(
-        _dl: &DataLayout,
-        _types: &Types,
+        layout: &Layout,
+        types: &Types,
         operands: &mut HashMap<Arc<Operand>, &'module llvm_ir::Operand>,
         globals: &HashMap<&str, Arc<Constant>>,
         locals: &HashMap<&Name, Arc<Operand>>,
         gep: &'module llvm_ir::instruction::GetElementPtr,
     ) -> Result<Self, Error> {
+        let indices: Vec<_> = gep.indices.iter().map(constant_index).collect();
+        let field_offset = match &*gep.address.get_type(types) {
+            llvm_ir::Type::PointerType { pointee_type, .. } => {
+                layout.field_gep_offset(pointee_type, &indices)
+            }
+            _ => None,
+        };
         Ok(GetElementPtr {
             pointer: Operand::new(operands, globals, locals, &gep.address)?,
+            field_offset,
+            zero: Self::is_zero(gep),
+        })
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct InsertElement {
+    pub(crate) vector: Arc<Operand>,
+    pub(crate) element: Arc<Operand>,
+}
+
+impl InsertElement {
+    pub(crate) fn from_insertelement<'module>(
+        operands: &mut HashMap<Arc<Operand>, &'module llvm_ir::Operand>,
+        globals: &HashMap<&str, Arc<Constant>>,
+        locals: &HashMap<&Name, Arc<Operand>>,
+        i: &'module llvm_ir::instruction::InsertElement,
+    ) -> Result<Self, Error> {
+        Ok(InsertElement {
+            vector: Operand::new(operands, globals, locals, &i.vector)?,
+            element: Operand::new(operands, globals, locals, &i.element)?,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct InsertValue {
+    pub(crate) aggregate: Arc<Operand>,
+    pub(crate) element: Arc<Operand>,
+    pub(crate) indices: Vec<u32>,
+}
+
+impl InsertValue {
+    pub(crate) fn from_insertvalue<'module>(
+        operands: &mut HashMap<Arc<Operand>, &'module llvm_ir::Operand>,
+        globals: &HashMap<&str, Arc<Constant>>,
+        locals: &HashMap<&Name, Arc<Operand>>,
+        i: &'module llvm_ir::instruction::InsertValue,
+    ) -> Result<Self, Error> {
+        Ok(InsertValue {
+            aggregate: Operand::new(operands, globals, locals, &i.aggregate)?,
+            element: Operand::new(operands, globals, locals, &i.element)?,
+            indices: i.indices.clone(),
         })
     }
 }
@@ -240,14 +415,37 @@ impl Select {
     }
 }
 
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct ShuffleVector {
+    pub(crate) operand0: Arc<Operand>,
+    pub(crate) operand1: Arc<Operand>,
+}
+
+impl ShuffleVector {
+    pub(crate) fn from_shufflevector<'module>(
+        operands: &mut HashMap<Arc<Operand>, &'module llvm_ir::Operand>,
+        globals: &HashMap<&str, Arc<Constant>>,
+        locals: &HashMap<&Name, Arc<Operand>>,
+        i: &'module llvm_ir::instruction::ShuffleVector,
+    ) -> Result<Self, Error> {
+        Ok(ShuffleVector {
+            operand0: Operand::new(operands, globals, locals, &i.operand0)?,
+            operand1: Operand::new(operands, globals, locals, &i.operand1)?,
+        })
+    }
+}
+
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub struct Store {
     pub(crate) value: Arc<Operand>,
     pub(crate) pointer: Arc<Operand>,
+    /// Type of the stored value
+    pub(crate) ty: llvm_ir::TypeRef,
 }
 
 impl Store {
     pub(crate) fn from_store<'module>(
+        types: &Types,
         operands: &mut HashMap<Arc<Operand>, &'module llvm_ir::Operand>,
         globals: &HashMap<&str, Arc<Constant>>,
         locals: &HashMap<&Name, Arc<Operand>>,
@@ -256,6 +454,7 @@ impl Store {
         Ok(Store {
             pointer: Operand::new(operands, globals, locals, &store.address)?,
             value: Operand::new(operands, globals, locals, &store.value)?,
+            ty: store.value.get_type(types),
         })
     }
 }
@@ -280,28 +479,59 @@ impl Sub {
     }
 }
 
+/// `va_arg`
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct VAArg {
+    /// Pointer to the `va_list`
+    pub(crate) arg_list: Arc<Operand>,
+}
+
+impl VAArg {
+    pub(crate) fn from_va_arg<'module>(
+        operands: &mut HashMap<Arc<Operand>, &'module llvm_ir::Operand>,
+        globals: &HashMap<&str, Arc<Constant>>,
+        locals: &HashMap<&Name, Arc<Operand>>,
+        i: &'module llvm_ir::instruction::VAArg,
+    ) -> Result<Self, Error> {
+        Ok(VAArg {
+            arg_list: Operand::new(operands, globals, locals, &i.arg_list)?,
+        })
+    }
+}
+
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub enum Opcode {
     Add(Add),
     Alloca(Alloca),
+    AtomicRMW(AtomicRMW),
     BitCast(BitCast),
     Call(Call),
+    CmpXchg(CmpXchg),
+    ExtractElement(ExtractElement),
+    ExtractValue(ExtractValue),
     GetElementPtr(GetElementPtr),
     Icmp(Icmp),
+    InsertElement(InsertElement),
+    InsertValue(InsertValue),
     IntToPtr(IntToPtr),
+    /// The result is the exception caught by the unwind destination that
+    /// this instruction begins, see [`crate::llvm::Invoke`]
+    LandingPad,
     Load(Load),
     Phi(Phi),
     PtrToInt(PtrToInt),
     Select(Select),
+    ShuffleVector(ShuffleVector),
     Store(Store),
     Sub(Sub),
+    VAArg(VAArg),
     //
     Other,
 }
 
 impl Opcode {
     pub(crate) fn from_instruction<'module>(
-        dl: &'module DataLayout,
+        layout: &Layout,
         types: &'module Types,
         operands: &mut HashMap<Arc<Operand>, &'module llvm_ir::Operand>,
         globals: &HashMap<&str, Arc<Constant>>,
@@ -314,9 +544,13 @@ impl Opcode {
             }
 
             llvm_ir::Instruction::Alloca(alloca) => Opcode::Alloca(Alloca::from_alloca(
-                dl, types, operands, globals, locals, alloca,
+                layout, types, operands, globals, locals, alloca,
             )?),
 
+            llvm_ir::Instruction::AtomicRMW(rmw) => {
+                Opcode::AtomicRMW(AtomicRMW::from_atomicrmw(operands, globals, locals, rmw)?)
+            }
+
             llvm_ir::Instruction::BitCast(bitcast) => {
                 Opcode::BitCast(BitCast::from_bitcast(operands, globals, locals, bitcast)?)
             }
@@ -325,18 +559,40 @@ impl Opcode {
                 Opcode::Call(Call::from_call(operands, globals, locals, call)?)
             }
 
+            llvm_ir::Instruction::CmpXchg(cmpxchg) => Opcode::CmpXchg(CmpXchg::from_cmpxchg(
+                types, operands, globals, locals, cmpxchg,
+            )?),
+
+            llvm_ir::Instruction::ExtractElement(ee) => Opcode::ExtractElement(
+                ExtractElement::from_extractelement(operands, globals, locals, ee)?,
+            ),
+
+            llvm_ir::Instruction::ExtractValue(ev) => Opcode::ExtractValue(
+                ExtractValue::from_extractvalue(operands, globals, locals, ev)?,
+            ),
+
             llvm_ir::Instruction::GetElementPtr(gep) => Opcode::GetElementPtr(
-                GetElementPtr::from_gep(dl, types, operands, globals, locals, gep)?,
+                GetElementPtr::from_gep(layout, types, operands, globals, locals, gep)?,
             ),
 
             llvm_ir::Instruction::ICmp(icmp) => {
                 Opcode::Icmp(Icmp::from_icmp(operands, globals, locals, icmp)?)
             }
 
+            llvm_ir::Instruction::InsertElement(ie) => Opcode::InsertElement(
+                InsertElement::from_insertelement(operands, globals, locals, ie)?,
+            ),
+
+            llvm_ir::Instruction::InsertValue(iv) => Opcode::InsertValue(
+                InsertValue::from_insertvalue(operands, globals, locals, iv)?,
+            ),
+
             llvm_ir::Instruction::IntToPtr(itp) => {
                 Opcode::IntToPtr(IntToPtr::from_inttoptr(operands, globals, locals, itp)?)
             }
 
+            llvm_ir::Instruction::LandingPad(_) => Opcode::LandingPad,
+
             llvm_ir::Instruction::Load(load) => {
                 Opcode::Load(Load::from_load(operands, globals, locals, load)?)
             }
@@ -353,14 +609,22 @@ impl Opcode {
                 Opcode::Select(Select::from_select(operands, globals, locals, select)?)
             }
 
+            llvm_ir::Instruction::ShuffleVector(sv) => Opcode::ShuffleVector(
+                ShuffleVector::from_shufflevector(operands, globals, locals, sv)?,
+            ),
+
             llvm_ir::Instruction::Store(store) => {
-                Opcode::Store(Store::from_store(operands, globals, locals, store)?)
+                Opcode::Store(Store::from_store(types, operands, globals, locals, store)?)
             }
 
             llvm_ir::Instruction::Sub(sub) => {
                 Opcode::Sub(Sub::from_sub(operands, globals, locals, sub)?)
             }
 
+            llvm_ir::Instruction::VAArg(va_arg) => {
+                Opcode::VAArg(VAArg::from_va_arg(operands, globals, locals, va_arg)?)
+            }
+
             _ => Opcode::Other,
         })
     }
@@ -370,16 +634,27 @@ impl Opcode {
             Opcode::Add(a) => vec![a.operand0.clone(), a.operand1.clone()],
             Opcode::Alloca(Alloca { .. }) => vec![],
             Opcode::BitCast(BitCast { pointer }) => vec![pointer.clone()],
+            Opcode::AtomicRMW(a) => vec![a.pointer.clone(), a.value.clone()],
             Opcode::Call(c) => c.operands(),
-            Opcode::GetElementPtr(GetElementPtr { pointer }) => vec![pointer.clone()],
+            Opcode::CmpXchg(c) => {
+                vec![c.pointer.clone(), c.expected.clone(), c.replacement.clone()]
+            }
+            Opcode::ExtractElement(e) => vec![e.vector.clone()],
+            Opcode::ExtractValue(e) => vec![e.aggregate.clone()],
+            Opcode::GetElementPtr(GetElementPtr { pointer, .. }) => vec![pointer.clone()],
             Opcode::Icmp(i) => vec![i.operand0.clone(), i.operand1.clone()],
+            Opcode::InsertElement(i) => vec![i.vector.clone(), i.element.clone()],
+            Opcode::InsertValue(i) => vec![i.aggregate.clone(), i.element.clone()],
             Opcode::IntToPtr(i) => vec![i.int.clone()],
+            Opcode::LandingPad => vec![],
             Opcode::Load(l) => vec![l.pointer.clone()],
             Opcode::Phi(i) => i.values.clone(),
             Opcode::PtrToInt(i) => vec![i.pointer.clone()],
             Opcode::Select(s) => vec![s.true_value.clone(), s.false_value.clone()],
+            Opcode::ShuffleVector(s) => vec![s.operand0.clone(), s.operand1.clone()],
             Opcode::Store(s) => vec![s.value.clone(), s.pointer.clone()],
             Opcode::Sub(i) => vec![i.minuend.clone(), i.subtrahend.clone()],
+            Opcode::VAArg(v) => vec![v.arg_list.clone()],
             //
             Opcode::Other => vec![],
         }
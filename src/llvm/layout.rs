@@ -0,0 +1,317 @@
+// SPDX-License-Identifier: BSD-3-Clause
+//! Sizes, alignments, and field offsets of LLVM types.
+//!
+//! `llvm_ir` parses the module's data layout string, but doesn't compute type
+//! sizes (see <https://github.com/cdisselkoen/llvm-ir/issues/31>), so this
+//! module does. All sizes and offsets are in bytes. Functions return `None`
+//! for types without a size (e.g., opaque structs, functions, labels).
+
+use std::collections::HashMap;
+
+use llvm_ir::{
+    module::DataLayout,
+    types::{FPType, NamedStructDef, Types},
+    Type, TypeRef,
+};
+
+use crate::arc::Arc;
+
+use super::constant::Constant;
+
+#[derive(Clone, Debug)]
+pub struct Layout {
+    dl: DataLayout,
+    /// Definitions of named structs, `None` if opaque
+    structs: HashMap<String, Option<TypeRef>>,
+}
+
+/// `None` on overflow
+fn round_up(n: u64, align: u64) -> Option<u64> {
+    if align <= 1 {
+        Some(n)
+    } else {
+        n.div_ceil(align).checked_mul(align)
+    }
+}
+
+impl Layout {
+    pub(crate) fn new(dl: &DataLayout, types: &Types) -> Self {
+        let mut structs = HashMap::new();
+        for name in types.all_struct_names() {
+            let def = match types.named_struct_def(name) {
+                Some(NamedStructDef::Defined(ty)) => Some(ty.clone()),
+                Some(NamedStructDef::Opaque) | None => None,
+            };
+            structs.insert(name.clone(), def);
+        }
+        Layout {
+            dl: dl.clone(),
+            structs,
+        }
+    }
+
+    /// Look through named structs to their definitions
+    fn resolve<'a>(&'a self, ty: &'a Type) -> Option<&'a Type> {
+        match ty {
+            Type::NamedStructType { name } => match self.structs.get(name) {
+                Some(Some(def)) => Some(def),
+                _ => None,
+            },
+            _ => Some(ty),
+        }
+    }
+
+    /// ABI alignment
+    pub fn align_of(&self, ty: &Type) -> Option<u64> {
+        let alignments = &self.dl.alignments;
+        let bits = match self.resolve(ty)? {
+            Type::IntegerType { bits } => alignments.int_alignment(*bits).abi,
+            Type::PointerType { addr_space, .. } => {
+                alignments.ptr_alignment(*addr_space).alignment.abi
+            }
+            Type::FPType(fpt) => match fpt {
+                FPType::Half | FPType::Single | FPType::Double | FPType::FP128 => {
+                    alignments.fp_alignment(*fpt).abi
+                }
+                FPType::BFloat => 16,
+                FPType::X86_FP80 | FPType::PPC_FP128 => 128,
+            },
+            Type::VectorType { .. } => {
+                let size = self.store_size_of(ty)?;
+                alignments
+                    .vec_alignment(u32::try_from(size.checked_mul(8)?).ok()?)
+                    .abi
+            }
+            Type::ArrayType { element_type, .. } => return self.align_of(element_type),
+            Type::StructType {
+                element_types,
+                is_packed,
+            } => {
+                if *is_packed {
+                    return Some(1);
+                }
+                let mut align = u64::from(alignments.agg_alignment().abi / 8).max(1);
+                for elem in element_types {
+                    align = align.max(self.align_of(elem)?);
+                }
+                return Some(align);
+            }
+            _ => return None,
+        };
+        Some(u64::from(bits / 8).max(1))
+    }
+
+    /// Size of a pointer in the default address space
+    pub fn pointer_size(&self) -> u64 {
+        u64::from(self.dl.alignments.ptr_alignment(0).size / 8)
+    }
+
+    /// Whether a value of this type could hold a pointer. Conservatively
+    /// `true` for types with unknown contents (e.g., opaque structs).
+    pub(crate) fn may_hold_pointer(&self, ty: &Type) -> bool {
+        match self.resolve(ty) {
+            None => true,
+            Some(Type::IntegerType { .. } | Type::FPType(_) | Type::X86_MMXType) => false,
+            Some(Type::ArrayType { element_type, .. } | Type::VectorType { element_type, .. }) => {
+                self.may_hold_pointer(element_type)
+            }
+            Some(Type::StructType { element_types, .. }) => {
+                element_types.iter().any(|t| self.may_hold_pointer(t))
+            }
+            Some(_) => true,
+        }
+    }
+
+    /// Number of bytes that may be overwritten by a store of this type
+    fn store_size_of(&self, ty: &Type) -> Option<u64> {
+        match self.resolve(ty)? {
+            Type::IntegerType { bits } => Some(u64::from(*bits).div_ceil(8)),
+            Type::PointerType { addr_space, .. } => Some(u64::from(
+                self.dl.alignments.ptr_alignment(*addr_space).size / 8,
+            )),
+            Type::FPType(fpt) => Some(match fpt {
+                FPType::Half | FPType::BFloat => 2,
+                FPType::Single => 4,
+                FPType::Double => 8,
+                FPType::X86_FP80 => 10,
+                FPType::FP128 | FPType::PPC_FP128 => 16,
+            }),
+            Type::VectorType {
+                element_type,
+                num_elements,
+                ..
+            } => {
+                let elem_bits = match self.resolve(element_type)? {
+                    Type::IntegerType { bits } => u64::from(*bits),
+                    _ => self.store_size_of(element_type)?.checked_mul(8)?,
+                };
+                Some(elem_bits.checked_mul(*num_elements as u64)?.div_ceil(8))
+            }
+            resolved @ (Type::ArrayType { .. } | Type::StructType { .. }) => self.size_of(resolved),
+            _ => None,
+        }
+    }
+
+    /// Allocation size, i.e., the offset between consecutive elements of an
+    /// array of this type, including padding.
+    pub fn size_of(&self, ty: &Type) -> Option<u64> {
+        match self.resolve(ty)? {
+            Type::ArrayType {
+                element_type,
+                num_elements,
+            } => self
+                .size_of(element_type)?
+                .checked_mul(*num_elements as u64),
+            resolved @ Type::StructType {
+                element_types,
+                is_packed,
+            } => {
+                let mut size = 0;
+                for elem in element_types {
+                    if !*is_packed {
+                        size = round_up(size, self.align_of(elem)?)?;
+                    }
+                    size = size.checked_add(self.size_of(elem)?)?;
+                }
+                round_up(size, self.align_of(resolved)?)
+            }
+            resolved => round_up(self.store_size_of(resolved)?, self.align_of(resolved)?),
+        }
+    }
+
+    /// Offset of the `idx`th field of a struct type
+    pub fn field_offset(&self, ty: &Type, idx: usize) -> Option<u64> {
+        match self.resolve(ty)? {
+            Type::StructType {
+                element_types,
+                is_packed,
+            } => {
+                let mut offset = 0;
+                for (i, elem) in element_types.iter().enumerate() {
+                    if !*is_packed {
+                        offset = round_up(offset, self.align_of(elem)?)?;
+                    }
+                    if i == idx {
+                        return Some(offset);
+                    }
+                    offset = offset.checked_add(self.size_of(elem)?)?;
+                }
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// Byte offset computed by a `getelementptr` with the given source
+    /// element type and indices, or `None` if any index isn't a constant.
+    pub(crate) fn gep_offset(&self, source: &Type, indices: &[Option<i64>]) -> Option<i64> {
+        let mut indices = indices.iter();
+        let first = (*indices.next()?)?;
+        let mut offset = first.checked_mul(i64::try_from(self.size_of(source)?).ok()?)?;
+        let mut current = source;
+        for idx in indices {
+            let idx = (*idx)?;
+            match self.resolve(current)? {
+                resolved @ Type::StructType { element_types, .. } => {
+                    let field = usize::try_from(idx).ok()?;
+                    let field_offset = i64::try_from(self.field_offset(resolved, field)?).ok()?;
+                    offset = offset.checked_add(field_offset)?;
+                    current = element_types.get(field)?;
+                }
+                Type::ArrayType { element_type, .. } | Type::VectorType { element_type, .. } => {
+                    let size = i64::try_from(self.size_of(element_type)?).ok()?;
+                    offset = offset.checked_add(idx.checked_mul(size)?)?;
+                    current = element_type;
+                }
+                _ => return None,
+            }
+        }
+        Some(offset)
+    }
+
+    /// Like [`Layout::gep_offset`], but only for `getelementptr`s that select
+    /// a field of the pointed-to object, i.e., whose first index is zero.
+    /// Others perform pointer arithmetic across objects or array elements.
+    pub(crate) fn field_gep_offset(&self, source: &Type, indices: &[Option<i64>]) -> Option<u64> {
+        if indices.first() != Some(&Some(0)) {
+            return None;
+        }
+        u64::try_from(self.gep_offset(source, indices)?).ok()
+    }
+
+    /// Size of the largest named struct type
+    pub(crate) fn max_struct_size(&self) -> u64 {
+        self.structs
+            .values()
+            .flatten()
+            .filter_map(|ty| self.size_of(ty))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// The non-aggregate constants inside an initializer of the given type,
+    /// along with their offsets from the start of the initializer.
+    pub(crate) fn initializer_offsets<'c>(
+        &self,
+        ty: &Type,
+        init: &'c Arc<Constant>,
+    ) -> Vec<(u64, &'c Arc<Constant>)> {
+        let mut out = Vec::new();
+        self.collect_initializer_offsets(ty, init, 0, &mut out);
+        out
+    }
+
+    fn collect_initializer_offsets<'c>(
+        &self,
+        ty: &Type,
+        init: &'c Arc<Constant>,
+        base: u64,
+        out: &mut Vec<(u64, &'c Arc<Constant>)>,
+    ) {
+        match (self.resolve(ty), &**init) {
+            (Some(resolved @ Type::StructType { element_types, .. }), Constant::Struct(s)) => {
+                for (idx, (elem_ty, field)) in element_types.iter().zip(&s.fields).enumerate() {
+                    // If the layout is unknown (or the offset overflows),
+                    // conservatively place the field at the start of the
+                    // struct.
+                    let offset = self
+                        .field_offset(resolved, idx)
+                        .and_then(|o| base.checked_add(o))
+                        .unwrap_or(base);
+                    self.collect_initializer_offsets(elem_ty, field, offset, out);
+                }
+            }
+            (Some(Type::ArrayType { element_type, .. }), Constant::Array(elems)) => {
+                let size = self.size_of(element_type);
+                for (idx, elem) in elems.iter().enumerate() {
+                    let offset = size
+                        .and_then(|s| s.checked_mul(idx as u64))
+                        .and_then(|o| base.checked_add(o))
+                        .unwrap_or(base);
+                    self.collect_initializer_offsets(element_type, elem, offset, out);
+                }
+            }
+            (Some(Type::VectorType { element_type, .. }), Constant::Vector(elems)) => {
+                let size = self.size_of(element_type);
+                for (idx, elem) in elems.iter().enumerate() {
+                    let offset = size
+                        .and_then(|s| s.checked_mul(idx as u64))
+                        .and_then(|o| base.checked_add(o))
+                        .unwrap_or(base);
+                    self.collect_initializer_offsets(element_type, elem, offset, out);
+                }
+            }
+            (_, Constant::Struct(s)) => {
+                for field in &s.fields {
+                    self.collect_initializer_offsets(ty, field, base, out);
+                }
+            }
+            (_, Constant::Array(elems) | Constant::Vector(elems)) => {
+                for elem in elems {
+                    self.collect_initializer_offsets(ty, elem, base, out);
+                }
+            }
+            _ => out.push((base, init)),
+        }
+    }
+}
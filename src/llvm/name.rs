@@ -25,6 +25,10 @@ impl GlobalName {
         GlobalName(s)
     }
 
+    pub(crate) fn get(&self) -> &str {
+        &self.0
+    }
+
     pub(crate) fn _from_str(s: &str) -> Self {
         GlobalName(s.to_string())
     }
@@ -124,6 +128,15 @@ impl BlockName {
             name: b.name.clone(),
         }
     }
+
+    /// A pseudo-block of an external function, holding the allocation sites
+    /// of its signatures
+    pub(crate) fn signature(parent_function: UArc<FunctionName>) -> Self {
+        Self {
+            parent_function,
+            name: llvm_ir::Name::from("signature"),
+        }
+    }
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
@@ -164,6 +177,10 @@ impl InstructionName {
             idx,
         }
     }
+
+    pub fn parent_function(&self) -> &UArc<FunctionName> {
+        &self.parent_function
+    }
 }
 
 impl Display for InstructionName {
@@ -216,6 +233,15 @@ pub enum LocalName {
     Instruction(UArc<InstructionName>),
 }
 
+impl LocalName {
+    pub fn parent_function(&self) -> &UArc<FunctionName> {
+        match self {
+            LocalName::Parameter(p) => &p.parent_function,
+            LocalName::Instruction(i) => &i.parent_function,
+        }
+    }
+}
+
 impl Display for LocalName {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
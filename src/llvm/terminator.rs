@@ -14,6 +14,9 @@ use super::operand::{Callee, Operand};
 pub struct Invoke {
     pub callee: Callee,
     pub args: Vec<Arc<Operand>>,
+    /// The `landingpad` that begins the unwind destination, if any (there is
+    /// none for Windows-style exception handling)
+    pub unwind: Option<Arc<Operand>>,
 }
 
 impl Invoke {
@@ -21,6 +24,7 @@ impl Invoke {
         operands: &mut HashMap<Arc<Operand>, &'module llvm_ir::Operand>,
         globals: &HashMap<&str, Arc<Constant>>,
         locals: &HashMap<&Name, Arc<Operand>>,
+        landing_pads: &HashMap<&Name, Arc<Operand>>,
         invoke: &'module llvm_ir::terminator::Invoke,
     ) -> Result<Self, Error> {
         Ok(Invoke {
@@ -35,6 +39,7 @@ impl Invoke {
                     Operand::new(operands, globals, locals, op).expect("Malformed LLVM module!")
                 })
                 .collect(),
+            unwind: landing_pads.get(&invoke.exception_label).cloned(),
         })
     }
 
@@ -48,6 +53,29 @@ impl Invoke {
     }
 }
 
+/// Resumes propagation of an exception caught by a `landingpad`
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Resume {
+    pub(crate) operand: Arc<Operand>,
+}
+
+impl Resume {
+    pub(crate) fn from_resume<'module>(
+        operands: &mut HashMap<Arc<Operand>, &'module llvm_ir::Operand>,
+        globals: &HashMap<&str, Arc<Constant>>,
+        locals: &HashMap<&Name, Arc<Operand>>,
+        resume: &'module llvm_ir::terminator::Resume,
+    ) -> Result<Self, Error> {
+        Ok(Resume {
+            operand: Operand::new(operands, globals, locals, &resume.operand)?,
+        })
+    }
+
+    pub(crate) fn operands(&self) -> Vec<Arc<Operand>> {
+        vec![self.operand.clone()]
+    }
+}
+
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub struct Ret {
     pub(crate) operand: Option<Arc<Operand>>,
@@ -80,6 +108,7 @@ impl Ret {
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub enum TerminatorOpcode {
     Invoke(Invoke),
+    Resume(Resume),
     Ret(Ret),
     Other,
 }
@@ -89,11 +118,19 @@ impl TerminatorOpcode {
         operands: &mut HashMap<Arc<Operand>, &'module llvm_ir::Operand>,
         globals: &HashMap<&str, Arc<Constant>>,
         locals: &HashMap<&Name, Arc<Operand>>,
+        landing_pads: &HashMap<&Name, Arc<Operand>>,
         t: &'module llvm_ir::Terminator,
     ) -> Result<Self, Error> {
         Ok(match t {
-            llvm_ir::Terminator::Invoke(invoke) => {
-                TerminatorOpcode::Invoke(Invoke::from_invoke(operands, globals, locals, invoke)?)
+            llvm_ir::Terminator::Invoke(invoke) => TerminatorOpcode::Invoke(Invoke::from_invoke(
+                operands,
+                globals,
+                locals,
+                landing_pads,
+                invoke,
+            )?),
+            llvm_ir::Terminator::Resume(resume) => {
+                TerminatorOpcode::Resume(Resume::from_resume(operands, globals, locals, resume)?)
             }
             llvm_ir::Terminator::Ret(ret) => {
                 TerminatorOpcode::Ret(Ret::from_ret(operands, globals, locals, ret)?)
@@ -102,7 +139,6 @@ impl TerminatorOpcode {
             // llvm_ir::Terminator::CondBr(_) => todo!(),
             // llvm_ir::Terminator::Switch(_) => todo!(),
             // llvm_ir::Terminator::IndirectBr(_) => todo!(),
-            // llvm_ir::Terminator::Resume(_) => todo!(),
             // llvm_ir::Terminator::Unreachable(_) => todo!(),
             // llvm_ir::Terminator::CleanupRet(_) => todo!(),
             // llvm_ir::Terminator::CatchRet(_) => todo!(),
@@ -115,6 +151,7 @@ impl TerminatorOpcode {
     pub(crate) fn operands(&self) -> Vec<Arc<Operand>> {
         match self {
             TerminatorOpcode::Invoke(t) => t.operands(),
+            TerminatorOpcode::Resume(t) => t.operands(),
             TerminatorOpcode::Ret(t) => t.operands(),
             TerminatorOpcode::Other => Vec::new(),
         }
@@ -1,6 +1,7 @@
 // SPDX-License-Identifier: BSD-3-Clause
 use std::collections::HashMap;
 use std::io::{self, Write};
+use std::path::Path;
 
 use anyhow::{anyhow, Context, Error, Result};
 use clap::Parser;
@@ -12,6 +13,7 @@ mod alloc;
 pub mod analysis;
 mod arc;
 mod cli;
+mod context;
 mod hash;
 mod klimited;
 mod lattice;
@@ -20,7 +22,7 @@ mod llvm;
 mod signatures;
 mod union;
 
-use analysis::pointer;
+use analysis::{introspect, pointer, unification, widen};
 
 #[cfg(feature = "dhat-heap")]
 #[global_allocator]
@@ -40,6 +42,84 @@ fn setup_global_subscriber() -> impl Drop {
     _guard
 }
 
+fn parse_module(path: &Path) -> Result<llvm::Module> {
+    let llvm_module = llvm_ir::Module::from_bc_path(path)
+        .map_err(Error::msg)
+        .with_context(|| format!("Couldn't parse LLVM bitcode module at {}", path.display()))?;
+    let mut operands: HashMap<arc::Arc<llvm::Operand>, &llvm_ir::Operand> =
+    // just a guess:
+        HashMap::with_capacity(llvm_module.global_vars.len() + (8 * llvm_module.functions.len()));
+    llvm::Module::new(&llvm_module, &mut operands).context("Malformed LLVM module")
+}
+
+/// Load signatures and check them against the module
+fn load_signatures(sources: &[String], module: &llvm::Module) -> Result<signatures::Signatures> {
+    let mut signatures =
+        signatures::Signatures::load(sources).context("Couldn't load points-to signatures")?;
+    for err in signatures.check_arities(module) {
+        eprintln!("warning: {err}; skipping it");
+    }
+    Ok(signatures)
+}
+
+/// Print skeleton signatures for the declarations in the module that lack
+/// them, see `signatures::suggest`
+fn suggest(module_path: &Path, signatures: &[String]) -> Result<()> {
+    let module = parse_module(module_path)?;
+    let signatures = load_signatures(signatures, &module)?;
+    // The callgraph is only used to report call sites, so a cheap
+    // context-insensitive analysis suffices.
+    let opts = pointer::Options {
+        check_assertions: false,
+        check_strict: false,
+        contexts: 0,
+        debug: false,
+        field_sensitive: false,
+        function_contexts: HashMap::new(),
+        heap_contexts: 0,
+        library: false,
+        metrics: false,
+        opaque_externs: false,
+        sound_externs: true,
+        unification: false,
+        widening: None,
+    };
+    let outs = pointer::analysis(&module, &signatures, &opts, &context::CallSite);
+    let skeleton = signatures::suggest::skeleton(&module, &signatures, &outs.callgraph);
+    let mut stdout = io::stdout().lock();
+    serde_json::to_writer_pretty(&mut stdout, &skeleton)?;
+    writeln!(stdout)?;
+    Ok(())
+}
+
+/// Print signatures for the exported functions of a library, see
+/// `analysis::summary`
+fn extract(module_path: &Path, signatures: &[String]) -> Result<()> {
+    let module = parse_module(module_path)?;
+    let signatures = load_signatures(signatures, &module)?;
+    let opts = pointer::Options {
+        check_assertions: false,
+        check_strict: false,
+        contexts: 0,
+        debug: false,
+        field_sensitive: false,
+        function_contexts: HashMap::new(),
+        heap_contexts: 0,
+        library: true,
+        metrics: false,
+        opaque_externs: false,
+        sound_externs: true,
+        unification: false,
+        widening: None,
+    };
+    let outs = pointer::analysis(&module, &signatures, &opts, &context::CallSite);
+    let summary = analysis::summary::signatures(&module, &outs);
+    let mut stdout = io::stdout().lock();
+    serde_json::to_writer_pretty(&mut stdout, &summary)?;
+    writeln!(stdout)?;
+    Ok(())
+}
+
 fn main() -> Result<()> {
     #[cfg(feature = "dhat-heap")]
     let _profiler = dhat::Profiler::new_heap();
@@ -50,41 +130,117 @@ fn main() -> Result<()> {
         setup_global_subscriber();
     }
 
-    let signatures = if let Some(signatures_path) = args.signatures {
-        let signatures_string = std::fs::read_to_string(signatures_path)
-            .context("Couldn't read points-to signatures")?;
-        signatures::Signatures::new(
-            serde_json::from_str(&signatures_string)
-                .context("Couldn't deserialize points-to signatures")?,
-        )
-        .context("Couldn't construct points-to signatures")?
-    } else {
-        signatures::Signatures::default()
-    };
+    match &args.command {
+        Some(cli::Command::Signatures(cli::SignaturesCommand::Suggest { module, signatures })) => {
+            return suggest(module, signatures)
+        }
+        Some(cli::Command::Signatures(cli::SignaturesCommand::Extract { module, signatures })) => {
+            return extract(module, signatures)
+        }
+        None => (),
+    }
+    let module_path = args.module.as_ref().expect("Missing module");
 
-    let llvm_module = llvm_ir::Module::from_bc_path(&args.module)
-        .map_err(Error::msg)
-        .with_context(|| {
-            format!(
-                "Couldn't parse LLVM bitcode module at {}",
-                args.module.display()
-            )
-        })?;
-    let mut operands: HashMap<arc::Arc<llvm::Operand>, &llvm_ir::Operand> =
-    // just a guess:
-        HashMap::with_capacity(llvm_module.global_vars.len() + (8 * llvm_module.functions.len()));
-    let module = llvm::Module::new(&llvm_module, &mut operands).context("Malformed LLVM module")?;
-    drop(operands);
+    let module = parse_module(module_path)?;
+    let signatures = load_signatures(&args.signatures, &module)?;
+
+    if args.signature_diagnostics {
+        let decls = module.decls.keys().map(|f| (*f).get());
+        let extern_globals = module
+            .globals
+            .iter()
+            .filter(|(_, g)| g.initializer.is_none())
+            .map(|(g_name, _)| g_name.get());
+        let diags = signatures.diagnose(decls.chain(extern_globals));
+        let mut stdout = io::stdout().lock();
+        writeln!(stdout, "multiply_matched_signatures")?;
+        writeln!(stdout, "---------------------------")?;
+        for (name, matches) in &diags.multiply_matched {
+            writeln!(stdout, "{name}")?;
+            for m in matches {
+                let applied = if m.applied { " (applied)" } else { "" };
+                writeln!(stdout, "  {}: {}{}", m.source, m.regex, applied)?;
+            }
+        }
+        writeln!(stdout)?;
+        writeln!(stdout, "unmatched_signatures")?;
+        writeln!(stdout, "--------------------")?;
+        for (source, regex) in &diags.unmatched {
+            writeln!(stdout, "{source}: {regex}")?;
+        }
+        writeln!(stdout)?;
+    }
+
+    let mut function_contexts = HashMap::new();
+    if args.introspective {
+        let pre_opts = pointer::Options {
+            check_assertions: false,
+            check_strict: false,
+            contexts: 0,
+            debug: args.debug,
+            field_sensitive: false,
+            function_contexts: HashMap::new(),
+            heap_contexts: 0,
+            library: false,
+            metrics: false,
+            opaque_externs: args.opaque_externs,
+            sound_externs: !args.unsound_externs,
+            unification: false,
+            widening: None,
+        };
+        let pre = pointer::analysis(&module, &signatures, &pre_opts, &context::CallSite);
+        let thresholds = introspect::Thresholds {
+            max_points_to: args.introspective_max_points_to,
+            max_callers: args.introspective_max_callers,
+        };
+        function_contexts = introspect::depths(&module, &pre, args.contexts, &thresholds);
+    }
+    if let Some(depths_path) = &args.context_depths {
+        let depths_string =
+            std::fs::read_to_string(depths_path).context("Couldn't read context depths")?;
+        let depths = context::ContextDepths::new(
+            serde_json::from_str(&depths_string).context("Couldn't deserialize context depths")?,
+        )
+        .context("Couldn't construct context depths")?;
+        function_contexts.extend(depths.for_module(&module));
+    }
 
     let opts = pointer::Options {
         check_assertions: args.check == cli::Check::Default || args.check == cli::Check::Strict,
         check_strict: args.check == cli::Check::Strict,
         contexts: args.contexts,
         debug: args.debug,
+        field_sensitive: args.field_sensitive,
+        function_contexts,
+        heap_contexts: args.heap_contexts,
+        library: false,
         metrics: args.metrics,
+        opaque_externs: args.opaque_externs,
+        sound_externs: !args.unsound_externs,
         unification: args.unification,
+        widening: args.widen.map(|budget| widen::Widening {
+            budget,
+            summary: match args.widen_to {
+                cli::WidenTo::Top => widen::Summary::Top,
+                cli::WidenTo::Type => widen::Summary::Type,
+            },
+        }),
+    };
+    let outs = if args.steensgaard {
+        unification::analysis(&module, &signatures, &opts)
+    } else {
+        match args.context_policy {
+            cli::ContextPolicy::CallSite => {
+                pointer::analysis(&module, &signatures, &opts, &context::CallSite)
+            }
+            cli::ContextPolicy::Object => {
+                pointer::analysis(&module, &signatures, &opts, &context::ObjectSensitive)
+            }
+            cli::ContextPolicy::Type => {
+                pointer::analysis(&module, &signatures, &opts, &context::TypeSensitive)
+            }
+        }
     };
-    let outs = pointer::analysis(&module, &signatures, &opts);
 
     if !args.quiet {
         let mut stdout = io::stdout().lock();
@@ -96,12 +252,16 @@ fn main() -> Result<()> {
         writeln!(stdout)?;
         writeln!(stdout, "operand_points_to")?;
         writeln!(stdout, "-----------------")?;
-        // TODO: Improve printing of contexts
         for pts in &outs.operand_points_to {
             writeln!(
                 stdout,
-                "{:?}: {} --> {}",
-                (*pts.0).clone().into_iter().collect::<Vec<_>>(),
+                "[{}]: {} --> {}",
+                (*pts.0)
+                    .clone()
+                    .into_iter()
+                    .map(|e| e.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
                 *pts.1,
                 *pts.2
             )?;
@@ -134,6 +294,8 @@ fn main() -> Result<()> {
             writeln!(stdout, "invalid memcpy srcs: {}", m.invalid_memcpy_srcs)?;
             writeln!(stdout, "invalid stores: {}", m.invalid_stores)?;
             writeln!(stdout, "points-to top: {}", m.points_to_top)?;
+            writeln!(stdout, "points-to opaque: {}", m.points_to_opaque)?;
+            writeln!(stdout, "widened points-to sets: {}", m.widened)?;
         }
     }
 
@@ -1,7 +1,13 @@
 // SPDX-License-Identifier: BSD-3-Clause
+use std::cell::OnceCell;
 use std::collections::HashMap;
+use std::path::PathBuf;
 
-use regex::RegexSet;
+use regex::{Regex, RegexSet};
+
+use crate::llvm::Module;
+
+pub mod suggest;
 
 #[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
 #[serde(rename_all = "kebab-case")]
@@ -11,69 +17,592 @@ pub enum AllocType {
     Top,
 }
 
-// TODO: Something for `getline` &co.
 #[allow(clippy::enum_variant_names)]
 #[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum Signature {
-    // TODO: Implement
-    CallsArg { arg: usize },
-    ReturnAlloc { r#type: AllocType },
-    // ReturnAllocOnce { r#type: AllocType },
+    /// Calls the function(s) that argument `arg` points to, passing the
+    /// arguments at indices `args` as its parameters
+    CallsArg {
+        arg: usize,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        args: Vec<usize>,
+    },
+    /// Returns a fresh allocation. The size of heap allocations is the
+    /// product of the constant arguments at `size-arg` and `count-arg`, if
+    /// given.
+    ReturnAlloc {
+        r#type: AllocType,
+        #[serde(default, rename = "size-arg", skip_serializing_if = "Option::is_none")]
+        size_arg: Option<usize>,
+        #[serde(default, rename = "count-arg", skip_serializing_if = "Option::is_none")]
+        count_arg: Option<usize>,
+    },
+    /// Returns the same allocation every time it's called
+    ReturnAllocOnce { r#type: AllocType },
+    /// Returns a copy of argument `arg`
     ReturnAliasesArg { arg: usize },
-    // ReturnAliasesArgReachable { arg: usize },
+    /// Returns a pointer to anything reachable from argument `arg`
+    ReturnAliasesArgReachable { arg: usize },
+    /// Returns a pointer to `global`
     ReturnPointsToGlobal { global: String },
-    // ReturnAliasesGlobal { global: String },
-    // ReturnAliasesGlobalReachable { global: String },
-    // ArgAlloc { arg: usize },
-    // ArgAllocOnce { arg: usize },
+    /// Returns the pointer stored in `global`
+    ReturnAliasesGlobal { global: String },
+    /// Returns a pointer to anything reachable from `global`
+    ReturnAliasesGlobalReachable { global: String },
+    /// Stores a fresh heap allocation into argument `arg`
+    ArgAlloc { arg: usize },
+    /// Stores the same heap allocation into argument `arg` every time it's
+    /// called
+    ArgAllocOnce { arg: usize },
+    /// Copies the memory pointed to by argument `src` to argument `dst`
     ArgMemcpyArg { dst: usize, src: usize },
-    // ArgMemcpyArgReachable { dst: usize, src: usize },
-    // ArgMemcpyGlobal { dst: usize, global: String },
-    // ArgMemcpyGlobalReachable { dst: usize, global: String },
-    // ArgPointsToGlobal { arg: usize, global: String },
+    /// Copies anything reachable from argument `src` to argument `dst`
+    ArgMemcpyArgReachable { dst: usize, src: usize },
+    /// Copies the memory of `global` to argument `dst`
+    ArgMemcpyGlobal { dst: usize, global: String },
+    /// Copies anything reachable from `global` to argument `dst`
+    ArgMemcpyGlobalReachable { dst: usize, global: String },
+    /// Stores a pointer to `global` into argument `arg`
+    ArgPointsToGlobal { arg: usize, global: String },
+    /// `global` points to a fresh allocation, if it isn't defined in the
+    /// module. Applies if the key matches a declared function or global.
+    GlobalPointsToFresh { global: String },
+    /// Doesn't throw C++ exceptions, other than those the analysis models
+    /// itself (e.g., for `__cxa_throw`). Calls with `invoke` to functions
+    /// without this signature may throw anything.
+    Nothrow,
+    /// Has unknown effects: returns `Top`, and may store `Top` into anything
+    /// reachable from its arguments (unless `stores` is false) and call any
+    /// function reachable from them (unless `calls` is false). Functions
+    /// without signatures are modeled like this in sound mode.
+    Unknown {
+        #[serde(default = "yes", skip_serializing_if = "is_yes")]
+        stores: bool,
+        #[serde(default = "yes", skip_serializing_if = "is_yes")]
+        calls: bool,
+    },
+}
+
+fn yes() -> bool {
+    true
+}
+
+fn is_yes(b: &bool) -> bool {
+    *b
+}
+
+/// Signatures for functions and globals of the C and C++ standard libraries
+/// that the analysis relies on, including those it handles specially
+const DEFAULT: &str = include_str!("signatures/default.json");
+
+/// Curated signature libraries, selectable as `builtin:<name>` or
+/// `builtin:<name>@<version>`. Bump the version when a library changes in a
+/// way that affects analysis results.
+const BUILTINS: [(&str, u32, &str); 4] = [
+    ("cxx", 1, include_str!("signatures/cxx.json")),
+    ("libc", 1, include_str!("signatures/libc.json")),
+    ("openssl", 1, include_str!("signatures/openssl.json")),
+    ("rust", 1, include_str!("signatures/rust.json")),
+];
+
+const BUILTIN_PREFIX: &str = "builtin:";
+
+/// Prefix of regexes that match demangled C++ and Rust names, e.g.,
+/// `demangled:^std::vector<.*>::push_back\(`
+const DEMANGLED_PREFIX: &str = "demangled:";
+
+#[derive(Debug, thiserror::Error)]
+pub enum LoadError {
+    #[error(
+        "Unknown built-in signatures `{0}`, expected one of: {}",
+        builtin_names()
+    )]
+    UnknownBuiltin(String),
+    #[error("Couldn't read points-to signatures at {}", .0.display())]
+    Io(PathBuf, #[source] std::io::Error),
+    #[error("Couldn't deserialize points-to signatures from {0}")]
+    Json(String, #[source] serde_json::Error),
+    #[error("Invalid regex `{1}` in points-to signatures from {0}")]
+    Regex(String, String, #[source] regex::Error),
+    #[error("Couldn't compile the regexes in points-to signatures from {0}")]
+    RegexSet(String, #[source] regex::Error),
+    #[error("Invalid points-to signature for `{1}` in {0}: {2}")]
+    Invalid(String, String, &'static str),
+    #[error(
+        "Points-to signature for `{1}` in {0} refers to argument {3} of `{2}`, which has {4} parameters"
+    )]
+    Arity(String, String, String, usize, usize),
+}
+
+fn builtin_names() -> String {
+    BUILTINS
+        .iter()
+        .map(|(name, version, _)| format!("{BUILTIN_PREFIX}{name}@{version}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl Signature {
+    /// The global this signature refers to, if any
+    pub(crate) fn global(&self) -> Option<&str> {
+        match self {
+            Signature::ReturnPointsToGlobal { global }
+            | Signature::ReturnAliasesGlobal { global }
+            | Signature::ReturnAliasesGlobalReachable { global }
+            | Signature::ArgMemcpyGlobal { global, .. }
+            | Signature::ArgMemcpyGlobalReachable { global, .. }
+            | Signature::ArgPointsToGlobal { global, .. }
+            | Signature::GlobalPointsToFresh { global } => Some(global),
+            // No `_` pattern to ensure this is updated if the type changes
+            Signature::CallsArg { .. }
+            | Signature::ReturnAlloc { .. }
+            | Signature::ReturnAllocOnce { .. }
+            | Signature::ReturnAliasesArg { .. }
+            | Signature::ReturnAliasesArgReachable { .. }
+            | Signature::ArgAlloc { .. }
+            | Signature::ArgAllocOnce { .. }
+            | Signature::ArgMemcpyArg { .. }
+            | Signature::ArgMemcpyArgReachable { .. }
+            | Signature::Nothrow
+            | Signature::Unknown { .. } => None,
+        }
+    }
+
+    /// The indices of the arguments this signature refers to
+    fn args(&self) -> Vec<usize> {
+        match self {
+            Signature::CallsArg { arg, args } => {
+                std::iter::once(*arg).chain(args.clone()).collect()
+            }
+            Signature::ReturnAlloc {
+                size_arg,
+                count_arg,
+                ..
+            } => size_arg.iter().chain(count_arg).copied().collect(),
+            Signature::ReturnAliasesArg { arg }
+            | Signature::ReturnAliasesArgReachable { arg }
+            | Signature::ArgAlloc { arg }
+            | Signature::ArgAllocOnce { arg }
+            | Signature::ArgPointsToGlobal { arg, .. } => vec![*arg],
+            Signature::ArgMemcpyArg { dst, src }
+            | Signature::ArgMemcpyArgReachable { dst, src } => {
+                vec![*dst, *src]
+            }
+            Signature::ArgMemcpyGlobal { dst, .. }
+            | Signature::ArgMemcpyGlobalReachable { dst, .. } => vec![*dst],
+            // No `_` pattern to ensure this is updated if the type changes
+            Signature::ReturnAllocOnce { .. }
+            | Signature::ReturnPointsToGlobal { .. }
+            | Signature::ReturnAliasesGlobal { .. }
+            | Signature::ReturnAliasesGlobalReachable { .. }
+            | Signature::GlobalPointsToFresh { .. }
+            | Signature::Nothrow
+            | Signature::Unknown { .. } => Vec::new(),
+        }
+    }
+
+    /// Check for mistakes that don't depend on the module being analyzed
+    fn validate(&self) -> Result<(), &'static str> {
+        match self {
+            Signature::ReturnAlloc {
+                size_arg,
+                count_arg,
+                ..
+            } => {
+                if size_arg.is_none() && count_arg.is_some() {
+                    return Err("count-arg requires size-arg");
+                }
+                if size_arg.is_some() && size_arg == count_arg {
+                    return Err("size-arg and count-arg are the same argument");
+                }
+            }
+            Signature::ArgMemcpyArg { dst, src }
+            | Signature::ArgMemcpyArgReachable { dst, src }
+                if dst == src =>
+            {
+                return Err("dst and src are the same argument");
+            }
+            _ => (),
+        }
+        if self.global().is_some_and(str::is_empty) {
+            return Err("empty global name");
+        }
+        Ok(())
+    }
 }
 
+/// The signatures for a regex in a signatures file
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(untagged)]
+pub enum Entry {
+    Signatures(Vec<Signature>),
+    Detailed {
+        /// If set, other entries of the same file that match the same name
+        /// are ignored
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        r#override: bool,
+        signatures: Vec<Signature>,
+        /// Free-form information about the entry, ignored by the analysis
+        #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+        metadata: serde_json::Map<String, serde_json::Value>,
+    },
+}
+
+impl Entry {
+    fn overrides(&self) -> bool {
+        match self {
+            Entry::Signatures(_) => false,
+            Entry::Detailed { r#override, .. } => *r#override,
+        }
+    }
+
+    fn signatures(&self) -> &[Signature] {
+        match self {
+            Entry::Signatures(signatures) | Entry::Detailed { signatures, .. } => signatures,
+        }
+    }
+}
+
+/// The signatures from a single source
 #[derive(Debug)]
-pub struct Signatures {
-    sigs: HashMap<String, Vec<Signature>>,
+struct Layer {
+    source: String,
+    /// As written, i.e., including any [`DEMANGLED_PREFIX`]
     regexes: Vec<String>,
+    entries: Vec<Entry>,
+    /// Regexes over raw names, and their indices in `regexes`
     set: RegexSet,
+    raw_indices: Vec<usize>,
+    /// Regexes over demangled names, and their indices in `regexes`
+    demangled_set: RegexSet,
+    demangled_indices: Vec<usize>,
+}
+
+impl Layer {
+    fn new(source: &str, entries: HashMap<String, Entry>) -> Result<Self, regex::Error> {
+        let (regexes, entries): (Vec<_>, Vec<_>) = entries.into_iter().unzip();
+        let mut raw = Vec::new();
+        let mut raw_indices = Vec::new();
+        let mut demangled = Vec::new();
+        let mut demangled_indices = Vec::new();
+        for (i, regex) in regexes.iter().enumerate() {
+            if let Some(regex) = regex.strip_prefix(DEMANGLED_PREFIX) {
+                demangled.push(regex);
+                demangled_indices.push(i);
+            } else {
+                raw.push(regex.as_str());
+                raw_indices.push(i);
+            }
+        }
+        let set = RegexSet::new(raw)?;
+        let demangled_set = RegexSet::new(demangled)?;
+        Ok(Layer {
+            source: source.to_string(),
+            regexes,
+            entries,
+            set,
+            raw_indices,
+            demangled_set,
+            demangled_indices,
+        })
+    }
+
+    /// Indices of the regexes that match `name`
+    fn all_matches(&self, name: &Name) -> Vec<usize> {
+        let mut matches: Vec<_> = self
+            .set
+            .matches(name.raw)
+            .into_iter()
+            .map(|m| self.raw_indices[m])
+            .collect();
+        if !self.demangled_indices.is_empty() {
+            if let Some(demangled) = name.demangled() {
+                matches.extend(
+                    self.demangled_set
+                        .matches(demangled)
+                        .into_iter()
+                        .map(|m| self.demangled_indices[m]),
+                );
+            }
+        }
+        matches
+    }
+
+    /// Indices of the entries that apply to `name`
+    fn matches(&self, name: &Name) -> Vec<usize> {
+        let matches = self.all_matches(name);
+        if matches.iter().any(|m| self.entries[*m].overrides()) {
+            matches
+                .into_iter()
+                .filter(|m| self.entries[*m].overrides())
+                .collect()
+        } else {
+            matches
+        }
+    }
+}
+
+/// A name to match against signature regexes, demangled at most once and only
+/// if some regex needs it
+struct Name<'a> {
+    raw: &'a str,
+    demangled: OnceCell<Option<String>>,
+}
+
+impl<'a> Name<'a> {
+    fn new(raw: &'a str) -> Self {
+        Name {
+            raw,
+            demangled: OnceCell::new(),
+        }
+    }
+
+    fn demangled(&self) -> Option<&str> {
+        self.demangled.get_or_init(|| demangle(self.raw)).as_deref()
+    }
 }
 
+/// The demangled Rust (legacy or v0) or C++ (Itanium) name, if `name` is
+/// mangled. Rust names are demangled without their hashes.
+pub fn demangle(name: &str) -> Option<String> {
+    if let Ok(demangled) = rustc_demangle::try_demangle(name) {
+        return Some(format!("{demangled:#}"));
+    }
+    cpp_demangle::Symbol::new(name).ok()?.demangle().ok()
+}
+
+/// A regex that matches a name, see [`Diagnostics`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Match {
+    pub source: String,
+    pub regex: String,
+    /// Whether the signatures of this regex are used for the name
+    pub applied: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Diagnostics {
+    /// Names matched by more than one regex, in increasing order of
+    /// precedence
+    pub multiply_matched: Vec<(String, Vec<Match>)>,
+    /// Sources and regexes that match none of the names. Built-in and
+    /// default signatures are not reported.
+    pub unmatched: Vec<(String, String)>,
+}
+
+/// Signatures from several sources. For each name, only the signatures from
+/// the last source with a matching regex are used. Within a source, the
+/// signatures of all matching regexes are combined, unless some of them are
+/// marked `override`, in which case only those are used.
+#[derive(Debug)]
+pub struct Signatures {
+    /// In increasing order of precedence, starting with the defaults
+    layers: Vec<Layer>,
+    /// Signatures that are skipped for the declarations they match, as they
+    /// refer to arguments the declarations don't have, see
+    /// [`Signatures::check_arities`]
+    misfits: HashMap<String, Vec<Signature>>,
+}
+
+const DEFAULT_SOURCE: &str = "default";
+
+/// Just the default signatures
 impl Default for Signatures {
     fn default() -> Self {
+        let sigs = serde_json::from_str(DEFAULT).expect("Invalid default signatures");
         Signatures {
-            sigs: HashMap::new(),
-            regexes: Vec::new(),
-            set: RegexSet::new::<[String; 0], _>([]).unwrap(),
+            layers: vec![Layer::new(DEFAULT_SOURCE, sigs).expect("Invalid default signatures")],
+            misfits: HashMap::new(),
         }
     }
 }
 
 impl Signatures {
+    /// The given signatures, falling back to the default ones for names they
+    /// don't match
     pub fn new(sigs: HashMap<String, Vec<Signature>>) -> Result<Self, regex::Error> {
-        let regexes = sigs.keys().map(|s| s.to_string()).collect();
-        let set = RegexSet::new(sigs.keys())?;
-        Ok(Signatures { sigs, regexes, set })
+        let mut signatures = Self::default();
+        let entries = sigs
+            .into_iter()
+            .map(|(regex, sigs)| (regex, Entry::Signatures(sigs)))
+            .collect();
+        signatures.layers.push(Layer::new("signatures", entries)?);
+        Ok(signatures)
+    }
+
+    /// Load and validate signatures from each source in order, falling back
+    /// to the default ones. A source is either `builtin:<name>` (optionally
+    /// suffixed with `@<version>`) or a path to a JSON file. Later sources
+    /// take precedence over earlier ones.
+    pub fn load<S: AsRef<str>>(sources: &[S]) -> Result<Self, LoadError> {
+        let mut signatures = Self::default();
+        for source in sources {
+            let source = source.as_ref();
+            let entries = Self::read(source)?;
+            Self::validate(source, &entries)?;
+            // The regexes were each checked by `validate`, but together they
+            // may still exceed the size limits of a `RegexSet`
+            let layer = Layer::new(source, entries)
+                .map_err(|e| LoadError::RegexSet(source.to_string(), e))?;
+            signatures.layers.push(layer);
+        }
+        Ok(signatures)
+    }
+
+    fn read(source: &str) -> Result<HashMap<String, Entry>, LoadError> {
+        let json = if let Some(builtin) = source.strip_prefix(BUILTIN_PREFIX) {
+            let (name, version) = match builtin.split_once('@') {
+                Some((name, version)) => (name, Some(version)),
+                None => (builtin, None),
+            };
+            let (_, _, json) = BUILTINS
+                .iter()
+                .find(|(n, v, _)| {
+                    *n == name && version.is_none_or(|version| version == v.to_string())
+                })
+                .ok_or_else(|| LoadError::UnknownBuiltin(builtin.to_string()))?;
+            json.to_string()
+        } else {
+            std::fs::read_to_string(source).map_err(|e| LoadError::Io(PathBuf::from(source), e))?
+        };
+        serde_json::from_str(&json).map_err(|e| LoadError::Json(source.to_string(), e))
+    }
+
+    fn validate(source: &str, entries: &HashMap<String, Entry>) -> Result<(), LoadError> {
+        for (regex, entry) in entries {
+            Regex::new(regex.strip_prefix(DEMANGLED_PREFIX).unwrap_or(regex))
+                .map_err(|e| LoadError::Regex(source.to_string(), regex.clone(), e))?;
+            for sig in entry.signatures() {
+                sig.validate()
+                    .map_err(|e| LoadError::Invalid(source.to_string(), regex.clone(), e))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that the signatures that apply to each declared function only
+    /// refer to arguments it has, i.e., are below its number of parameters
+    /// (unless it's variadic). Signatures that don't are skipped for that
+    /// function, and reported.
+    pub fn check_arities(&mut self, module: &Module) -> Vec<LoadError> {
+        let mut errors = Vec::new();
+        let mut decls: Vec<_> = module.decls.iter().collect();
+        decls.sort_unstable_by_key(|(f, _)| (**f).get());
+        for (f, decl) in decls {
+            if decl.is_var_arg {
+                continue;
+            }
+            let name = Name::new(f.get());
+            let Some(layer) = self
+                .layers
+                .iter()
+                .rev()
+                .find(|l| !l.matches(&name).is_empty())
+            else {
+                continue;
+            };
+            for m in layer.matches(&name) {
+                let arity = decl.parameters.len();
+                for sig in layer.entries[m].signatures() {
+                    if let Some(arg) = sig.args().into_iter().find(|arg| *arg >= arity) {
+                        errors.push(LoadError::Arity(
+                            layer.source.clone(),
+                            layer.regexes[m].clone(),
+                            f.get().to_string(),
+                            arg,
+                            arity,
+                        ));
+                        self.misfits
+                            .entry(f.get().to_string())
+                            .or_default()
+                            .push(sig.clone());
+                    }
+                }
+            }
+        }
+        errors
     }
 
     pub fn _has_signatures_for(&self, func: &str) -> bool {
-        self.set.matches(func).into_iter().next().is_some()
+        let name = Name::new(func);
+        self.layers.iter().any(|l| !l.all_matches(&name).is_empty())
     }
 
     pub fn signatures_for(&self, func: &str) -> Option<Vec<Signature>> {
-        let mut matched = false;
-        let mut sigs = Vec::new();
-        for m in self.set.matches(func) {
-            matched = true;
-            sigs.extend(self.sigs[&self.regexes[m]].clone());
-        }
-        if matched {
-            Some(sigs)
-        } else {
-            None
+        let name = Name::new(func);
+        let misfits = self.misfits.get(func);
+        self.layers.iter().rev().find_map(|layer| {
+            let matches = layer.matches(&name);
+            if matches.is_empty() {
+                None
+            } else {
+                Some(
+                    matches
+                        .into_iter()
+                        .flat_map(|m| layer.entries[m].signatures().iter().cloned())
+                        .filter(|sig| misfits.is_none_or(|ms| !ms.contains(sig)))
+                        .collect(),
+                )
+            }
+        })
+    }
+
+    /// Report names (i.e., of declared functions and globals) matched by
+    /// several regexes, and regexes from signature files that match none of
+    /// them.
+    pub fn diagnose<'a>(&self, names: impl IntoIterator<Item = &'a str>) -> Diagnostics {
+        let mut diagnostics = Diagnostics::default();
+        let mut used: Vec<Vec<bool>> = self
+            .layers
+            .iter()
+            .map(|l| vec![false; l.regexes.len()])
+            .collect();
+        let mut names: Vec<_> = names.into_iter().collect();
+        names.sort_unstable();
+        names.dedup();
+        for raw in names {
+            let name = Name::new(raw);
+            let mut matches = Vec::new();
+            let mut applied = true;
+            for (i, layer) in self.layers.iter().enumerate().rev() {
+                let layer_matches = layer.all_matches(&name);
+                if layer_matches.is_empty() {
+                    continue;
+                }
+                let applied_matches = layer.matches(&name);
+                for m in layer_matches {
+                    used[i][m] = true;
+                    matches.push(Match {
+                        source: layer.source.clone(),
+                        regex: layer.regexes[m].clone(),
+                        applied: applied && applied_matches.contains(&m),
+                    });
+                }
+                applied = false;
+            }
+            if matches.len() > 1 {
+                matches.reverse();
+                diagnostics
+                    .multiply_matched
+                    .push((raw.to_string(), matches));
+            }
         }
+        for (layer, used) in self.layers.iter().zip(used) {
+            if layer.source == DEFAULT_SOURCE || layer.source.starts_with(BUILTIN_PREFIX) {
+                continue;
+            }
+            let mut unmatched: Vec<_> = layer
+                .regexes
+                .iter()
+                .zip(used)
+                .filter(|(_, used)| !used)
+                .map(|(regex, _)| (layer.source.clone(), regex.clone()))
+                .collect();
+            unmatched.sort();
+            diagnostics.unmatched.extend(unmatched);
+        }
+        diagnostics
     }
 }
 
@@ -81,11 +610,190 @@ impl Signatures {
 mod tests {
     use std::collections::HashMap;
 
-    use super::Signatures;
+    use super::{AllocType, Entry, LoadError, Match, Signature, Signatures, BUILTINS};
 
     #[test]
     fn it_works() {
         let sigs = Signatures::new(HashMap::from([]));
         assert_eq!(None, sigs.unwrap().signatures_for("f"));
     }
+
+    #[test]
+    fn defaults() {
+        let sigs = Signatures::new(HashMap::from([(
+            "^malloc$".to_string(),
+            vec![Signature::ReturnAlloc {
+                r#type: AllocType::Stack,
+                size_arg: None,
+                count_arg: None,
+            }],
+        )]))
+        .unwrap();
+        assert_eq!(
+            Some(vec![Signature::ReturnAlloc {
+                r#type: AllocType::Stack,
+                size_arg: None,
+                count_arg: None,
+            }]),
+            sigs.signatures_for("malloc")
+        );
+        assert_eq!(Some(vec![Signature::Nothrow]), sigs.signatures_for("free"));
+        assert_eq!(None, sigs.signatures_for("fopen"));
+    }
+
+    #[test]
+    fn builtins() {
+        for (name, version, _) in BUILTINS {
+            Signatures::load(&[format!("builtin:{name}")]).unwrap();
+            Signatures::load(&[format!("builtin:{name}@{version}")]).unwrap();
+        }
+        assert!(matches!(
+            Signatures::load(&["builtin:nope"]),
+            Err(LoadError::UnknownBuiltin(_))
+        ));
+        assert!(matches!(
+            Signatures::load(&["builtin:libc@0"]),
+            Err(LoadError::UnknownBuiltin(_))
+        ));
+
+        let sigs = Signatures::load(&["builtin:libc", "builtin:cxx"]).unwrap();
+        assert_eq!(Some(vec![]), sigs.signatures_for("strlen"));
+        assert_eq!(Some(vec![]), sigs.signatures_for("_ZdlPv"));
+        assert_eq!(Some(vec![Signature::Nothrow]), sigs.signatures_for("free"));
+        assert_eq!(None, sigs.signatures_for("__rust_dealloc"));
+
+        let heap = Signature::ReturnAlloc {
+            r#type: AllocType::Heap,
+            size_arg: None,
+            count_arg: None,
+        };
+        assert_eq!(Some(vec![heap.clone()]), sigs.signatures_for("getenv"));
+        let sigs = Signatures::load(&["builtin:openssl"]).unwrap();
+        assert_eq!(Some(vec![heap]), sigs.signatures_for("SSL_new"));
+    }
+
+    #[test]
+    fn precedence() {
+        let dir = std::env::temp_dir().join(format!("yapall-sigs-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let generic = dir.join("generic.json");
+        let specific = dir.join("specific.json");
+        std::fs::write(
+            &generic,
+            r#"{
+              "^str": [],
+              "^strdup$": [ { "return-alloc": { "type": "heap" } } ],
+              "^x": [ { "return-alloc": { "type": "heap" } } ],
+              "^xstrdup$": { "override": true, "signatures": [] }
+            }"#,
+        )
+        .unwrap();
+        std::fs::write(
+            &specific,
+            r#"{ "^strdup$": [ { "return-alloc": { "type": "stack" } } ], "^nope$": [] }"#,
+        )
+        .unwrap();
+        let sigs =
+            Signatures::load(&[generic.to_str().unwrap(), specific.to_str().unwrap()]).unwrap();
+        let stack = Signature::ReturnAlloc {
+            r#type: AllocType::Stack,
+            size_arg: None,
+            count_arg: None,
+        };
+        let heap = Signature::ReturnAlloc {
+            r#type: AllocType::Heap,
+            size_arg: None,
+            count_arg: None,
+        };
+
+        // Later files take precedence
+        assert_eq!(Some(vec![stack]), sigs.signatures_for("strdup"));
+        // Matching regexes of the same file combine...
+        assert_eq!(Some(vec![heap]), sigs.signatures_for("xmalloc"));
+        // ...unless one overrides the others
+        assert_eq!(Some(vec![]), sigs.signatures_for("xstrdup"));
+        assert_eq!(Some(vec![Signature::Nothrow]), sigs.signatures_for("free"));
+
+        let diags = sigs.diagnose(["strdup", "xstrdup", "strlen", "free"]);
+        let generic = generic.to_str().unwrap().to_string();
+        let specific = specific.to_str().unwrap().to_string();
+        assert_eq!(
+            vec![
+                (
+                    "strdup".to_string(),
+                    vec![
+                        Match {
+                            source: generic.clone(),
+                            regex: "^str".to_string(),
+                            applied: false,
+                        },
+                        Match {
+                            source: generic.clone(),
+                            regex: "^strdup$".to_string(),
+                            applied: false,
+                        },
+                        Match {
+                            source: specific.clone(),
+                            regex: "^strdup$".to_string(),
+                            applied: true,
+                        },
+                    ]
+                ),
+                (
+                    "xstrdup".to_string(),
+                    vec![
+                        Match {
+                            source: generic.clone(),
+                            regex: "^x".to_string(),
+                            applied: false,
+                        },
+                        Match {
+                            source: generic.clone(),
+                            regex: "^xstrdup$".to_string(),
+                            applied: true,
+                        },
+                    ]
+                ),
+            ],
+            diags
+                .multiply_matched
+                .into_iter()
+                .map(|(name, mut ms)| {
+                    // Order within a source is unspecified
+                    ms.sort_by(|a, b| (&a.source, &a.regex).cmp(&(&b.source, &b.regex)));
+                    (name, ms)
+                })
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(vec![(specific, "^nope$".to_string())], diags.unmatched);
+        assert!(matches!(
+            serde_json::from_str(r#"{ "override": true, "signatures": [] }"#),
+            Ok(Entry::Detailed { .. })
+        ));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn demangled() {
+        let sigs = Signatures::new(HashMap::from([
+            (
+                r"demangled:^std::vector<.*>::push_back\(".to_string(),
+                vec![],
+            ),
+            (r"demangled:^alloc::vec::Vec<.*>::push$".to_string(), vec![]),
+            (r"^_ZN3foo".to_string(), vec![]),
+        ]))
+        .unwrap();
+        assert_eq!(
+            Some(vec![]),
+            sigs.signatures_for("_ZNSt6vectorIiSaIiEE9push_backERKi")
+        );
+        assert_eq!(
+            Some(vec![]),
+            sigs.signatures_for("_ZN5alloc3vec16Vec$LT$T$C$A$GT$4push17h0123456789abcdefE")
+        );
+        assert_eq!(Some(vec![]), sigs.signatures_for("_ZN3foo3barEv"));
+        assert_eq!(None, sigs.signatures_for("_ZNSt6vectorIiSaIiEE8pop_backEv"));
+        assert_eq!(None, sigs.signatures_for("push_back"));
+    }
 }
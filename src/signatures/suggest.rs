@@ -0,0 +1,177 @@
+// SPDX-License-Identifier: BSD-3-Clause
+//! Skeleton signatures for declarations that lack them.
+//!
+//! The guesses are based only on the types of the declarations, so they need
+//! to be reviewed before use:
+//!
+//! - No pointer parameters or return value: `[]`
+//! - Returns a pointer, but takes none: `return-alloc` on the heap
+//! - Takes and returns `char*`: `return-aliases-arg` of the first `char*`
+//!   parameter
+//!
+//! Other declarations get the `unknown` signature as a placeholder, which is
+//! as conservative as having no signature at all, and are marked as unsure in
+//! their metadata.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use llvm_ir::Type;
+
+use crate::{
+    arc::UArc,
+    llvm::{Decl, FunctionName, InstructionName, Module},
+    signatures::{demangle, AllocType, Entry, Signature, Signatures},
+};
+
+fn is_pointer(ty: &Type) -> bool {
+    matches!(ty, Type::PointerType { .. })
+}
+
+fn is_char_pointer(ty: &Type) -> bool {
+    matches!(ty, Type::PointerType { pointee_type, .. }
+        if matches!(**pointee_type, Type::IntegerType { bits: 8 }))
+}
+
+/// The guessed signatures, if a guess could be made
+fn guess(decl: &Decl) -> Option<Vec<Signature>> {
+    let pointer_params = decl.parameters.iter().filter(|p| is_pointer(p)).count();
+    if !is_pointer(&decl.return_type) {
+        return if pointer_params == 0 {
+            Some(Vec::new())
+        } else {
+            None
+        };
+    }
+    if pointer_params == 0 {
+        return Some(vec![Signature::ReturnAlloc {
+            r#type: AllocType::Heap,
+            size_arg: None,
+            count_arg: None,
+        }]);
+    }
+    if is_char_pointer(&decl.return_type) {
+        let arg = decl.parameters.iter().position(|p| is_char_pointer(p))?;
+        return Some(vec![Signature::ReturnAliasesArg { arg }]);
+    }
+    None
+}
+
+/// The signatures to suggest, and whether they're only a placeholder because
+/// no guess could be made
+fn suggestion(decl: &Decl) -> (Vec<Signature>, bool) {
+    match guess(decl) {
+        Some(signatures) => (signatures, false),
+        None => (
+            vec![Signature::Unknown {
+                stores: true,
+                calls: true,
+            }],
+            true,
+        ),
+    }
+}
+
+/// One entry for each declaration in `module` that `signatures` doesn't
+/// match, keyed by a regex that matches exactly its name. Metadata records the
+/// type of the declaration and the calls to it in `callgraph`.
+pub fn skeleton(
+    module: &Module,
+    signatures: &Signatures,
+    callgraph: &[(UArc<InstructionName>, UArc<FunctionName>)],
+) -> BTreeMap<String, Entry> {
+    let mut call_sites: HashMap<&UArc<FunctionName>, (usize, BTreeSet<String>)> = HashMap::new();
+    for (call, callee) in callgraph {
+        let (count, callers) = call_sites.entry(callee).or_default();
+        *count += 1;
+        callers.insert(call.parent_function().get().to_string());
+    }
+
+    let mut entries = BTreeMap::new();
+    for (name, decl) in &module.decls {
+        if signatures.signatures_for(name.get()).is_some() {
+            continue;
+        }
+        let (signatures, unsure) = suggestion(decl);
+        let mut metadata = serde_json::Map::new();
+        let params: Vec<_> = decl.parameters.iter().map(|p| p.to_string()).collect();
+        metadata.insert(
+            "type".to_string(),
+            format!("{} ({})", decl.return_type, params.join(", ")).into(),
+        );
+        if let Some(demangled) = demangle(name.get()) {
+            metadata.insert("demangled".to_string(), demangled.into());
+        }
+        let (count, callers) = call_sites.remove(name).unwrap_or_default();
+        metadata.insert("call-sites".to_string(), count.into());
+        metadata.insert(
+            "callers".to_string(),
+            callers.into_iter().collect::<Vec<_>>().into(),
+        );
+        if unsure {
+            metadata.insert("unsure".to_string(), true.into());
+        }
+        entries.insert(
+            format!("^{}$", regex::escape(name.get())),
+            Entry::Detailed {
+                r#override: false,
+                signatures,
+                metadata,
+            },
+        );
+    }
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use llvm_ir::types::Types;
+
+    use super::{guess, suggestion};
+    use crate::{
+        llvm::Decl,
+        signatures::{AllocType, Signature},
+    };
+
+    #[test]
+    fn guesses() {
+        let types = Types::blank_for_testing();
+        let char_ptr = types.pointer_to(types.i8());
+        let int_ptr = types.pointer_to(types.i32());
+        let decl = |parameters, return_type| Decl {
+            parameters,
+            return_type,
+            is_var_arg: false,
+        };
+
+        assert_eq!(Some(vec![]), guess(&decl(vec![types.i32()], types.void())));
+        assert_eq!(
+            Some(vec![Signature::ReturnAlloc {
+                r#type: AllocType::Heap,
+                size_arg: None,
+                count_arg: None,
+            }]),
+            guess(&decl(vec![types.i32()], int_ptr.clone()))
+        );
+        assert_eq!(
+            Some(vec![Signature::ReturnAliasesArg { arg: 1 }]),
+            guess(&decl(vec![types.i32(), char_ptr.clone()], char_ptr.clone()))
+        );
+        assert_eq!(None, guess(&decl(vec![int_ptr.clone()], types.void())));
+        assert_eq!(None, guess(&decl(vec![int_ptr.clone()], char_ptr)));
+
+        assert_eq!(
+            (vec![], false),
+            suggestion(&decl(vec![types.i32()], types.void()))
+        );
+        assert_eq!(
+            (
+                vec![Signature::Unknown {
+                    stores: true,
+                    calls: true
+                }],
+                true
+            ),
+            suggestion(&decl(vec![int_ptr], types.void()))
+        );
+    }
+}
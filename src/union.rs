@@ -1,5 +1,17 @@
 // SPDX-License-Identifier: BSD-3-Clause
+//! Union-find over allocations, used to merge allocations in unification mode
+//! (see `Options::unification`).
+//!
+//! Each allocation stores a pointer to its parent, and roots store their rank
+//! (an upper bound on the height of their tree). [`UnionFind::merge`] attaches
+//! the root of lower rank to the other, and [`UnionFind::lookup`] compresses
+//! the paths it follows, so both take nearly constant amortized time. The
+//! root of a class is its representative. Which member that is depends on
+//! the order of merges, so callers that need deterministic output should
+//! canonicalize (e.g., deduplicate) after all merges are done.
+
 use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicU8, Ordering};
 
 use crate::arc::Arc;
 
@@ -15,52 +27,37 @@ pub trait UnionFind: Sized {
 
     fn parent_mut_ref(&self) -> Self::MutRef<'_>;
 
-    fn grandparent(a: &Arc<Self>) -> Option<(Arc<Self>, Arc<Self>)>
-    where
-        Self: PartialEq,
-    {
-        match &*a.parent_ref() {
-            None => None,
-            Some(p) => {
-                debug_assert!(a != p);
-                match &*p.parent_ref() {
-                    None => None,
-                    Some(gp) => {
-                        debug_assert!(a != gp);
-                        debug_assert!(p != gp);
-                        Some((p.clone(), gp.clone()))
-                    }
-                }
-            }
-        }
-    }
+    /// Only meaningful for roots
+    fn rank(&self) -> &AtomicU8;
 
     fn lookup(a: &Arc<Self>) -> Arc<Self>
     where
         Self: PartialEq,
     {
-        let last = {
-            let mut current = a.clone();
-            while let Some((p, gp)) = Self::grandparent(&current) {
-                {
-                    *a.parent_mut_ref() = Some(gp.clone());
-                    current = p;
+        let mut root = a.clone();
+        loop {
+            let parent = (*root.parent_ref()).clone();
+            match parent {
+                None => break,
+                Some(p) => {
+                    debug_assert!(root != p);
+                    root = p;
                 }
             }
-            current
-        };
-        let bind = match &*last.parent_ref() {
-            None => last.clone(),
-            Some(p) => {
-                debug_assert!(a != p);
-                debug_assert!(last != *p);
-                p.clone()
+        }
+        // Path compression: point everything on the path directly at the root
+        let mut current = a.clone();
+        while current != root {
+            let parent = (*current.parent_ref()).clone();
+            let Some(p) = parent else { break };
+            if p != root {
+                *current.parent_mut_ref() = Some(root.clone());
             }
-        };
-        bind
+            current = p;
+        }
+        root
     }
 
-    // TODO: Merge by weight
     fn merge(a: &Arc<Self>, b: &Arc<Self>) -> bool
     where
         Self: PartialEq + PartialOrd,
@@ -73,11 +70,24 @@ pub trait UnionFind: Sized {
         if ar == br {
             return false;
         }
-        let (less, greater) = if ar < br { (ar, br) } else { (br, ar) };
-        debug_assert!(less != greater);
+        let (a_rank, b_rank) = (
+            ar.rank().load(Ordering::Acquire),
+            br.rank().load(Ordering::Acquire),
+        );
+        // Union by rank, breaking ties by order so that merging the same
+        // pairs in the same order always yields the same representatives
+        let (root, child) = if a_rank > b_rank || (a_rank == b_rank && ar < br) {
+            (ar, br)
+        } else {
+            (br, ar)
+        };
+        debug_assert!(root != child);
+        if a_rank == b_rank {
+            root.rank().fetch_add(1, Ordering::AcqRel);
+        }
         {
-            let mut parent = greater.parent_mut_ref();
-            *parent = Some(less);
+            let mut parent = child.parent_mut_ref();
+            *parent = Some(root);
         }
         true
     }
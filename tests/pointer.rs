@@ -3,18 +3,20 @@
 // TODO: Run each test at different levels of context sensitivity!
 
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     path::{Path, PathBuf},
     process::Command,
 };
 
 use yapall::{
-    analysis::pointer,
+    analysis::{introspect, pointer, summary, unification, widen},
+    context::{CallSite, ContextDepths, ContextPolicy, ObjectSensitive, TypeSensitive},
     llvm::constant::Constant,
     llvm::instruction::{Call, Opcode},
     pointer::Options,
     pointer::OutputRelations,
-    Alloc, Arc, Callee, FunctionName, Invoke, Module, Operand, Signatures, TerminatorOpcode, UArc,
+    Alloc, Arc, Callee, FunctionName, GlobalName, Invoke, LoadError, Module, Operand, Signature,
+    Signatures, TerminatorOpcode, UArc,
 };
 
 // ------------------------------------------------------------------
@@ -147,6 +149,55 @@ fn check_call(
     }
 }
 
+/// Arguments of each call or invoke of `callee` in `caller`
+fn call_args(module: &Module, caller: &str, callee: &str) -> Vec<Vec<Arc<Operand>>> {
+    let mut calls = Vec::new();
+    let mut check = |c: &Callee, args: &Vec<Arc<Operand>>| {
+        if let Callee::Operand(op) = c {
+            if let Operand::Constant(const_arc) = &**op {
+                if let Constant::Function(name) = &**const_arc {
+                    if **name == callee {
+                        calls.push(args.clone());
+                    }
+                }
+            }
+        }
+    };
+    for (f_name, f) in &module.functions {
+        if **f_name != caller {
+            continue;
+        }
+        for b in &f.blocks {
+            if let TerminatorOpcode::Invoke(Invoke { callee, args, .. }) =
+                b.terminator.opcode.as_ref()
+            {
+                check(callee, args);
+            }
+            for i in &b.instrs {
+                if let Opcode::Call(Call { callee, args, .. }) = i.opcode.as_ref() {
+                    check(callee, args);
+                }
+            }
+        }
+    }
+    calls
+}
+
+/// Allocations pointed to by the `arg`th argument of calls of `callee` in
+/// `caller`
+fn arg_points_to<'a>(
+    out: &'a OutputRelations,
+    module: &Module,
+    caller: &str,
+    callee: &str,
+    arg: usize,
+) -> HashSet<&'a Alloc> {
+    call_args(module, caller, callee)
+        .iter()
+        .flat_map(|args| points_to(out, &args[arg]))
+        .collect()
+}
+
 fn check_module(out: &OutputRelations, module: &Module) {
     for (f_name, f) in &module.functions {
         for b in &f.blocks {
@@ -164,6 +215,25 @@ fn check_module(out: &OutputRelations, module: &Module) {
     }
 }
 
+/// Check that heap allocations get the sizes their allocators' signatures
+/// compute from the size and count arguments
+fn check_alloc_sizes(module: &NamedModule, sizes: &[u64]) {
+    let out = check(module);
+    let heap: HashSet<_> = out
+        .operand_points_to
+        .iter()
+        .filter(|(_, _, a)| matches!(**a, Alloc::Heap(_)))
+        .map(|(_, _, a)| a.to_string())
+        .collect();
+    for size in sizes {
+        let suffix = format!("({size})");
+        assert!(
+            heap.iter().any(|a| a.ends_with(&suffix)),
+            "No heap allocation of size {size}: {heap:?}"
+        );
+    }
+}
+
 fn signatures(program: &str, dir: &str) -> Signatures {
     let path_str = format!("tests/{}/{}", dir, program);
     let path = Path::new(&path_str);
@@ -201,19 +271,193 @@ fn convert(program: &str, dir: &str, opt: u8) -> NamedModule {
 }
 
 fn check(module: &NamedModule) -> OutputRelations {
+    check_with(module, |_| ())
+}
+
+fn check_field_sensitive(module: &NamedModule) -> OutputRelations {
+    check_with(module, |opts| opts.field_sensitive = true)
+}
+
+fn check_heap_contexts(module: &NamedModule) -> OutputRelations {
+    check_with(module, |opts| opts.heap_contexts = 1)
+}
+
+/// Context-insensitive, except for functions named `xmalloc`
+fn check_xmalloc_contexts(module: &NamedModule) -> OutputRelations {
+    let depths = ContextDepths::new(HashMap::from([("^xmalloc$".to_string(), 1)])).unwrap();
+    check_with(module, |opts| {
+        opts.contexts = 0;
+        opts.function_contexts = depths.for_module(&module.module);
+        opts.heap_contexts = 1;
+    })
+}
+
+/// Contexts only for functions with at most one caller, as chosen by a
+/// context-insensitive pre-analysis. Returns the chosen depths.
+fn check_introspective(module: &NamedModule) -> HashMap<UArc<FunctionName>, u8> {
+    let pre = check_with(module, |opts| opts.contexts = 0);
+    let thresholds = introspect::Thresholds {
+        max_points_to: usize::MAX,
+        max_callers: 1,
+    };
+    let depths = introspect::depths(&module.module, &pre, 1, &thresholds);
+    check_with(module, |opts| {
+        opts.contexts = 1;
+        opts.function_contexts = depths.clone();
+    });
+    depths
+}
+
+/// Widen points-to sets of more than one allocation
+fn check_widened(module: &NamedModule, summary: widen::Summary) -> OutputRelations {
+    check_with(module, |opts| {
+        opts.widening = Some(widen::Widening { budget: 1, summary })
+    })
+}
+
+/// Check that with widening, a load still sees a store made through another
+/// pointer to the same allocation
+fn check_widened_alias(module: &NamedModule, summary: widen::Summary) {
+    let out = check_widened(module, summary);
+    assert!(out.metrics.as_ref().unwrap().widened > 0);
+    let loaded = arg_points_to(
+        &out,
+        &module.module,
+        "main",
+        "assert_points_to_something",
+        0,
+    );
+    assert!(
+        loaded.iter().any(|a| matches!(a, Alloc::Stack(_))),
+        "Load misses the store: {loaded:?}"
+    );
+}
+
+fn check_object_sensitive(module: &NamedModule) -> OutputRelations {
+    check_with_policy(module, |opts| opts.heap_contexts = 1, &ObjectSensitive)
+}
+
+fn check_type_sensitive(module: &NamedModule) -> OutputRelations {
+    check_with_policy(module, |opts| opts.heap_contexts = 1, &TypeSensitive)
+}
+
+fn check_with(module: &NamedModule, tweak: impl FnOnce(&mut Options)) -> OutputRelations {
+    check_with_policy(module, tweak, &CallSite)
+}
+
+fn check_with_policy(
+    module: &NamedModule,
+    tweak: impl FnOnce(&mut Options),
+    policy: &impl ContextPolicy,
+) -> OutputRelations {
     let sigs = signatures(&module.program, &module.dir);
-    let opts = Options {
+    let mut opts = Options {
         check_assertions: true,
         // This program intentionally constructs a nonsense pointer, triggering
         // an assertion failure before the test can complete.
         check_strict: module.program != "fail-assert-points-to-something.c",
         contexts: 1,
         debug: false,
+        field_sensitive: false,
+        function_contexts: HashMap::new(),
+        heap_contexts: 0,
+        library: false,
         metrics: true,
+        opaque_externs: false,
+        sound_externs: false,
         unification: true,
+        widening: None,
+    };
+    tweak(&mut opts);
+    let out = pointer::analysis(&module.module, &sigs, &opts, policy);
+    check_module(&out, &module.module);
+    check_canonicalized(&out);
+    out
+}
+
+/// Merged allocations are canonicalized, so the outputs only refer to their
+/// representatives
+fn check_canonicalized(out: &OutputRelations) {
+    let allocs = out
+        .operand_points_to
+        .iter()
+        .map(|(_, _, a)| a)
+        .chain(out.alloc_points_to.iter().flat_map(|(a, b)| [a, b]));
+    for a in allocs {
+        assert!(Alloc::is_representative(a), "Not canonicalized: {a}");
+    }
+}
+
+/// Check the signatures extracted from a library against the expected ones in
+/// `<program>.summary.json`
+fn check_summary(module: &NamedModule) {
+    let out = check_with(module, |opts| {
+        opts.contexts = 0;
+        opts.library = true;
+    });
+    let path = Path::new(&format!("tests/{}/{}", module.dir, module.program))
+        .with_extension("summary.json");
+    let expected: BTreeMap<String, Vec<Signature>> =
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+    assert_eq!(expected, summary::signatures(&module.module, &out));
+}
+
+/// Run the unification-based analysis, and check that its results include
+/// those of the (context-insensitive) inclusion-based analysis. Allocations
+/// are compared by name, as the two analyses create them separately.
+fn check_steensgaard(module: &NamedModule) -> OutputRelations {
+    let precise = check_with(module, |opts| opts.contexts = 0);
+    let sigs = signatures(&module.program, &module.dir);
+    let opts = Options {
+        check_assertions: false,
+        check_strict: false,
+        contexts: 0,
+        debug: false,
+        field_sensitive: false,
+        function_contexts: HashMap::new(),
+        heap_contexts: 0,
+        library: false,
+        metrics: true,
+        opaque_externs: false,
+        sound_externs: false,
+        unification: false,
+        widening: None,
     };
-    let out = pointer::analysis(&module.module, &sigs, &opts);
+    let out = unification::analysis(&module.module, &sigs, &opts);
     check_module(&out, &module.module);
+
+    let operand_points_to: HashSet<(&Operand, String)> = out
+        .operand_points_to
+        .iter()
+        .map(|(_, op, a)| (&**op, a.to_string()))
+        .collect();
+    for (_, op, a) in &precise.operand_points_to {
+        if **a != Alloc::Null {
+            assert!(
+                operand_points_to.contains(&(&**op, a.to_string())),
+                "{op} --> {a}"
+            );
+        }
+    }
+    let alloc_points_to: HashSet<(String, String)> = out
+        .alloc_points_to
+        .iter()
+        .map(|(a, b)| (a.to_string(), b.to_string()))
+        .collect();
+    for (a, b) in &precise.alloc_points_to {
+        if **b != Alloc::Null {
+            assert!(
+                alloc_points_to.contains(&(a.to_string(), b.to_string())),
+                "{a} --> {b}"
+            );
+        }
+    }
+    for f in &precise.reachable {
+        assert!(out.reachable.contains(f), "{f}");
+    }
+    for edge in &precise.callgraph {
+        assert!(out.callgraph.contains(edge), "{} --> {}", edge.0, edge.1);
+    }
     out
 }
 
@@ -237,6 +481,10 @@ fn sound(program: &str, opt: u8) -> NamedModule {
     convert(program, "pointer/soundness", opt)
 }
 
+fn summarized(program: &str, opt: u8) -> NamedModule {
+    convert(program, "pointer/summary", opt)
+}
+
 fn template(program: &str, opt: u8) -> NamedModule {
     convert(program, "pointer/templates", opt)
 }
@@ -275,6 +523,24 @@ fn properties() {
     }
 }
 
+#[test]
+fn alloc_sizes_o0() {
+    let module = sound("alloc-sizes.c", 0);
+    check_alloc_sizes(&module, &[16, 24, 56]);
+}
+
+#[test]
+fn alloc_sizes_o1() {
+    let module = sound("alloc-sizes.c", 1);
+    check_alloc_sizes(&module, &[16, 24, 56]);
+}
+
+#[test]
+fn alloc_sizes_o2() {
+    let module = sound("alloc-sizes.c", 2);
+    check_alloc_sizes(&module, &[16, 24, 56]);
+}
+
 #[test]
 fn alloca_o0() {
     let module = sound("alloca.c", 0);
@@ -347,6 +613,51 @@ fn r#array_o2() {
     let _out = check(&module);
 }
 
+#[test]
+fn atomic_o0() {
+    let module = sound("atomic.c", 0);
+    let _out = check(&module);
+}
+
+#[test]
+fn atomic_o1() {
+    let module = sound("atomic.c", 1);
+    let _out = check(&module);
+}
+
+#[test]
+fn atomic_o2() {
+    let module = sound("atomic.c", 2);
+    let _out = check(&module);
+}
+
+#[test]
+fn atomic_ptr_o0() {
+    if rustc().is_none() {
+        return;
+    }
+    let module = sound("atomic-ptr.rs", 0);
+    let _out = check(&module);
+}
+
+#[test]
+fn atomic_ptr_o1() {
+    if rustc().is_none() {
+        return;
+    }
+    let module = sound("atomic-ptr.rs", 1);
+    let _out = check(&module);
+}
+
+#[test]
+fn atomic_ptr_o2() {
+    if rustc().is_none() {
+        return;
+    }
+    let module = sound("atomic-ptr.rs", 2);
+    let _out = check(&module);
+}
+
 #[test]
 fn call_o0() {
     let module = sound("call.c", 0);
@@ -429,6 +740,24 @@ fn context_o2() {
     let _out = check(&module);
 }
 
+#[test]
+fn ctype_o0() {
+    let module = sound("ctype.c", 0);
+    let _out = check(&module);
+}
+
+#[test]
+fn ctype_o1() {
+    let module = sound("ctype.c", 1);
+    let _out = check(&module);
+}
+
+#[test]
+fn ctype_o2() {
+    let module = sound("ctype.c", 2);
+    let _out = check(&module);
+}
+
 // XFAIL: This proves that this assertion is being handled properly by tests
 #[test]
 #[should_panic(expected = "assertion failed: disjoint")]
@@ -504,6 +833,57 @@ fn fail_assert_unreachable_o2() {
     let _out = check(&module);
 }
 
+const FIELD_SENSITIVE_PROGRAMS: &[&str] = &[
+    "function-table.c",
+    "gep.c",
+    "global-array.c",
+    "global-expr.c",
+    "global-struct.c",
+    "memcpy.c",
+    "ptr-to-int-sub.c",
+    "stack-array.c",
+    "stack-struct.c",
+];
+
+#[test]
+fn field_sensitive_o0() {
+    for program in FIELD_SENSITIVE_PROGRAMS {
+        check_field_sensitive(&sound(program, 0));
+    }
+}
+
+#[test]
+fn field_sensitive_o1() {
+    for program in FIELD_SENSITIVE_PROGRAMS {
+        check_field_sensitive(&sound(program, 1));
+    }
+}
+
+#[test]
+fn field_sensitive_o2() {
+    for program in FIELD_SENSITIVE_PROGRAMS {
+        check_field_sensitive(&sound(program, 2));
+    }
+}
+
+#[test]
+fn extern_array_o0() {
+    let module = sound("extern-array.c", 0);
+    let _out = check(&module);
+}
+
+#[test]
+fn extern_array_o1() {
+    let module = sound("extern-array.c", 1);
+    let _out = check(&module);
+}
+
+#[test]
+fn extern_array_o2() {
+    let module = sound("extern-array.c", 2);
+    let _out = check(&module);
+}
+
 #[test]
 fn func_ptr_o0() {
     let module = sound("func-ptr.c", 0);
@@ -666,6 +1046,34 @@ fn global_struct_o2() {
     let _out = check(&module);
 }
 
+#[test]
+fn global_size() {
+    let module = sound("global-size.c", 0);
+    let _out = check(&module);
+    let layout = module.module.layout();
+    let size = |g: &str| module.module.globals[&Arc::new(GlobalName::from(g))].size(layout);
+    assert_eq!(Some(24), size("s"));
+    assert_eq!(Some(9), size("ps"));
+    assert_eq!(Some(15), size("arr"));
+    assert_eq!(None, size("empty"));
+    assert_eq!(None, size("ext"));
+    let s = llvm_ir::Type::NamedStructType {
+        name: "struct.s".to_string(),
+    };
+    assert_eq!(Some(8), layout.field_offset(&s, 1));
+    assert_eq!(Some(16), layout.field_offset(&s, 2));
+    let ps = llvm_ir::Type::NamedStructType {
+        name: "struct.ps".to_string(),
+    };
+    assert_eq!(Some(1), layout.field_offset(&ps, 1));
+    // Sizes that don't fit in 64 bits are unknown
+    let types = llvm_ir::types::Types::blank_for_testing();
+    assert_eq!(
+        None,
+        layout.size_of(&types.array_of(types.i64(), usize::MAX))
+    );
+}
+
 #[test]
 fn global_expr_o0() {
     let module = sound("global-expr.c", 0);
@@ -702,6 +1110,123 @@ fn heap_heap_o2() {
     let _out = check(&module);
 }
 
+#[test]
+fn heap_wrapper_o0() {
+    let module = precise("heap-wrapper.c", 0);
+    let _out = check_heap_contexts(&module);
+}
+
+#[test]
+fn heap_wrapper_o1() {
+    let module = precise("heap-wrapper.c", 1);
+    let _out = check_heap_contexts(&module);
+}
+
+#[test]
+fn heap_wrapper_o2() {
+    let module = precise("heap-wrapper.c", 2);
+    let _out = check_heap_contexts(&module);
+}
+
+#[test]
+fn heap_wrapper_function_contexts_o0() {
+    let module = precise("heap-wrapper.c", 0);
+    let _out = check_xmalloc_contexts(&module);
+}
+
+#[test]
+fn heap_wrapper_function_contexts_o1() {
+    let module = precise("heap-wrapper.c", 1);
+    let _out = check_xmalloc_contexts(&module);
+}
+
+#[test]
+fn heap_wrapper_function_contexts_o2() {
+    let module = precise("heap-wrapper.c", 2);
+    let _out = check_xmalloc_contexts(&module);
+}
+
+#[test]
+fn receiver_object_sensitive_o0() {
+    let module = precise("receiver.cpp", 0);
+    let _out = check_object_sensitive(&module);
+}
+
+#[test]
+fn receiver_object_sensitive_o1() {
+    let module = precise("receiver.cpp", 1);
+    let _out = check_object_sensitive(&module);
+}
+
+#[test]
+fn receiver_object_sensitive_o2() {
+    let module = precise("receiver.cpp", 2);
+    let _out = check_object_sensitive(&module);
+}
+
+#[test]
+fn receiver_type_sensitive_o0() {
+    let module = precise("receiver.cpp", 0);
+    let _out = check_type_sensitive(&module);
+}
+
+#[test]
+fn receiver_type_sensitive_o1() {
+    let module = precise("receiver.cpp", 1);
+    let _out = check_type_sensitive(&module);
+}
+
+#[test]
+fn receiver_type_sensitive_o2() {
+    let module = precise("receiver.cpp", 2);
+    let _out = check_type_sensitive(&module);
+}
+
+#[test]
+fn receiver_empty_object_sensitive_o0() {
+    let module = sound("receiver-empty.c", 0);
+    let _out = check_object_sensitive(&module);
+}
+
+#[test]
+fn receiver_empty_object_sensitive_o1() {
+    let module = sound("receiver-empty.c", 1);
+    let _out = check_object_sensitive(&module);
+}
+
+#[test]
+fn receiver_empty_object_sensitive_o2() {
+    let module = sound("receiver-empty.c", 2);
+    let _out = check_object_sensitive(&module);
+}
+
+#[test]
+fn introspective_o0() {
+    let module = sound("introspective.c", 0);
+    let depths = check_introspective(&module);
+    let depth = |name: &str| depths.iter().find(|(f, _)| ***f == name).map(|(_, d)| *d);
+    assert_eq!(depth("popular"), Some(0));
+    assert_eq!(depth("cheap"), Some(1));
+}
+
+#[test]
+fn introspective_o1() {
+    let module = sound("introspective.c", 1);
+    let depths = check_introspective(&module);
+    let depth = |name: &str| depths.iter().find(|(f, _)| ***f == name).map(|(_, d)| *d);
+    assert_eq!(depth("popular"), Some(0));
+    assert_eq!(depth("cheap"), Some(1));
+}
+
+#[test]
+fn introspective_o2() {
+    let module = sound("introspective.c", 2);
+    let depths = check_introspective(&module);
+    let depth = |name: &str| depths.iter().find(|(f, _)| ***f == name).map(|(_, d)| *d);
+    assert_eq!(depth("popular"), Some(0));
+    assert_eq!(depth("cheap"), Some(1));
+}
+
 #[test]
 fn indirect_call_o0() {
     let module = sound("indirect-call.c", 0);
@@ -803,21 +1328,18 @@ fn memcpy_o2() {
 }
 
 #[test]
-#[should_panic(expected = "assertion failed: !points_to_something")]
 fn memcpy_size_o0() {
     let module = precise("memcpy-size.c", 0);
     let _out = check(&module);
 }
 
 #[test]
-#[should_panic(expected = "assertion failed: !points_to_something")]
 fn memcpy_size_o1() {
     let module = precise("memcpy-size.c", 1);
     let _out = check(&module);
 }
 
 #[test]
-#[should_panic(expected = "assertion failed: !points_to_something")]
 fn memcpy_size_o2() {
     let module = precise("memcpy-size.c", 2);
     let _out = check(&module);
@@ -841,6 +1363,69 @@ fn new_o2() {
     let _out = check(&module);
 }
 
+#[test]
+fn opaque_o0() {
+    let module = precise("opaque.c", 0);
+    let out = check_with(&module, |opts| opts.opaque_externs = true);
+    let metrics = out.metrics.unwrap();
+    assert_eq!(metrics.points_to_top, 0);
+    assert!(metrics.points_to_opaque > 0);
+}
+
+#[test]
+fn opaque_o1() {
+    let module = precise("opaque.c", 1);
+    let out = check_with(&module, |opts| opts.opaque_externs = true);
+    let metrics = out.metrics.unwrap();
+    assert_eq!(metrics.points_to_top, 0);
+    assert!(metrics.points_to_opaque > 0);
+}
+
+#[test]
+fn opaque_o2() {
+    let module = precise("opaque.c", 2);
+    let out = check_with(&module, |opts| opts.opaque_externs = true);
+    let metrics = out.metrics.unwrap();
+    assert_eq!(metrics.points_to_top, 0);
+    assert!(metrics.points_to_opaque > 0);
+}
+
+#[test]
+fn opaque_escape_o0() {
+    let module = sound("opaque-escape.c", 0);
+    let out = check_with(&module, |opts| {
+        opts.opaque_externs = true;
+        opts.sound_externs = true;
+    });
+    let metrics = out.metrics.unwrap();
+    assert!(metrics.points_to_top > 0);
+    assert!(metrics.points_to_opaque > 0);
+}
+
+#[test]
+fn opaque_escape_o1() {
+    let module = sound("opaque-escape.c", 1);
+    let out = check_with(&module, |opts| {
+        opts.opaque_externs = true;
+        opts.sound_externs = true;
+    });
+    let metrics = out.metrics.unwrap();
+    assert!(metrics.points_to_top > 0);
+    assert!(metrics.points_to_opaque > 0);
+}
+
+#[test]
+fn opaque_escape_o2() {
+    let module = sound("opaque-escape.c", 2);
+    let out = check_with(&module, |opts| {
+        opts.opaque_externs = true;
+        opts.sound_externs = true;
+    });
+    let metrics = out.metrics.unwrap();
+    assert!(metrics.points_to_top > 0);
+    assert!(metrics.points_to_opaque > 0);
+}
+
 #[test]
 fn phi_o0() {
     let module = sound("phi.c", 0);
@@ -991,72 +1576,263 @@ fn template_shared_ptr_o2() {
     let _out = check(&module);
 }
 
-// XFAIL(TODO)
 #[test]
-#[should_panic(expected = "Bug!")]
-fn sig_arg_memcpy_arg_o0() {
-    let module = signature("arg-memcpy-arg.c", 0);
-    let _out = check(&module);
+fn unknown_extern_o0() {
+    let module = sound("unknown-extern.c", 0);
+    let out = check_with(&module, |opts| opts.sound_externs = true);
+    assert!(out.needs_signature.iter().any(|f| **f == "unknown"));
+    assert!(stores_top(&out));
+    check_callback(&out);
 }
 
 #[test]
-fn sig_arg_memcpy_arg_o1() {
-    let module = signature("arg-memcpy-arg.c", 1);
-    let _out = check(&module);
+fn unknown_extern_o1() {
+    let module = sound("unknown-extern.c", 1);
+    let out = check_with(&module, |opts| opts.sound_externs = true);
+    assert!(out.needs_signature.iter().any(|f| **f == "unknown"));
+    assert!(stores_top(&out));
+    check_callback(&out);
 }
 
 #[test]
-fn sig_arg_memcpy_arg_o2() {
-    let module = signature("arg-memcpy-arg.c", 2);
-    let _out = check(&module);
+fn unknown_extern_o2() {
+    let module = sound("unknown-extern.c", 2);
+    let out = check_with(&module, |opts| opts.sound_externs = true);
+    assert!(out.needs_signature.iter().any(|f| **f == "unknown"));
+    assert!(stores_top(&out));
+    check_callback(&out);
 }
 
 #[test]
-fn sig_return_alloc_o0() {
-    let module = signature("return-alloc.c", 0);
+fn sig_global_points_to_fresh_o0() {
+    let module = signature("global-points-to-fresh.c", 0);
     let _out = check(&module);
 }
 
 #[test]
-fn sig_return_alloc_o1() {
-    let module = signature("return-alloc.c", 1);
+fn sig_global_points_to_fresh_o1() {
+    let module = signature("global-points-to-fresh.c", 1);
     let _out = check(&module);
 }
 
 #[test]
-fn sig_return_alloc_o2() {
-    let module = signature("return-alloc.c", 2);
+fn sig_global_points_to_fresh_o2() {
+    let module = signature("global-points-to-fresh.c", 2);
     let _out = check(&module);
 }
 
-#[test]
-fn sig_return_aliases_arg_o0() {
-    let module = signature("return-aliases-arg.c", 0);
-    let _out = check(&module);
+/// Check that `callback` is reachable, and in the callgraph as a callee of
+/// the call to the external function that calls it
+fn check_callback(out: &OutputRelations) {
+    assert!(out.reachable.iter().any(|f| **f == "callback"));
+    assert!(out.callgraph.iter().any(|(_, f)| **f == "callback"));
 }
 
-#[test]
-fn sig_return_aliases_arg_o1() {
-    let module = signature("return-aliases-arg.c", 1);
-    let _out = check(&module);
+/// Whether `Top` was stored into some allocation
+fn stores_top(out: &OutputRelations) -> bool {
+    out.alloc_points_to
+        .iter()
+        .any(|(_, a)| matches!(**a, Alloc::Top))
 }
 
 #[test]
-fn sig_return_aliases_arg_o2() {
-    let module = signature("return-aliases-arg.c", 2);
-    let _out = check(&module);
+fn sig_calls_arg_o0() {
+    let module = signature("calls-arg.c", 0);
+    let out = check(&module);
+    check_callback(&out);
 }
 
 #[test]
-fn sig_return_points_to_global_o0() {
-    let module = signature("return-points-to-global.c", 0);
-    let _out = check(&module);
+fn sig_calls_arg_o1() {
+    let module = signature("calls-arg.c", 1);
+    let out = check(&module);
+    check_callback(&out);
 }
 
 #[test]
-fn sig_return_points_to_global_o1() {
-    let module = signature("return-points-to-global.c", 1);
-    let _out = check(&module);
+fn sig_calls_arg_o2() {
+    let module = signature("calls-arg.c", 2);
+    let out = check(&module);
+    check_callback(&out);
+}
+
+#[test]
+fn sig_calls_arg_arity_o0() {
+    let module = signature("calls-arg.c", 0);
+    let mut sigs = Signatures::new(HashMap::from([(
+        "^calls_arg$".to_string(),
+        vec![Signature::CallsArg {
+            arg: 0,
+            args: vec![2],
+        }],
+    )]))
+    .unwrap();
+    assert!(matches!(
+        sigs.check_arities(&module.module)[..],
+        [LoadError::Arity(_, _, _, 2, 2)]
+    ));
+    assert_eq!(Some(vec![]), sigs.signatures_for("calls_arg"));
+    assert!(signatures(&module.program, &module.dir)
+        .check_arities(&module.module)
+        .is_empty());
+}
+
+#[test]
+fn sig_unknown_o0() {
+    let module = signature("unknown.c", 0);
+    let out = check(&module);
+    assert!(stores_top(&out));
+    check_callback(&out);
+}
+
+#[test]
+fn sig_unknown_o1() {
+    let module = signature("unknown.c", 1);
+    let out = check(&module);
+    assert!(stores_top(&out));
+    check_callback(&out);
+}
+
+#[test]
+fn sig_unknown_o2() {
+    let module = signature("unknown.c", 2);
+    let out = check(&module);
+    assert!(stores_top(&out));
+    check_callback(&out);
+}
+
+#[test]
+fn sig_unknown_no_stores_o0() {
+    let module = signature("unknown-no-stores.c", 0);
+    let out = check(&module);
+    assert!(!stores_top(&out));
+    check_callback(&out);
+}
+
+#[test]
+fn sig_unknown_no_stores_o1() {
+    let module = signature("unknown-no-stores.c", 1);
+    let out = check(&module);
+    assert!(!stores_top(&out));
+    check_callback(&out);
+}
+
+#[test]
+fn sig_unknown_no_stores_o2() {
+    let module = signature("unknown-no-stores.c", 2);
+    let out = check(&module);
+    assert!(!stores_top(&out));
+    check_callback(&out);
+}
+
+#[test]
+fn sig_unknown_no_calls_o0() {
+    let module = signature("unknown-no-calls.c", 0);
+    let out = check(&module);
+    assert!(stores_top(&out));
+    assert!(!out.reachable.iter().any(|f| **f == "callback"));
+}
+
+#[test]
+fn sig_unknown_no_calls_o1() {
+    let module = signature("unknown-no-calls.c", 1);
+    let out = check(&module);
+    assert!(stores_top(&out));
+    assert!(!out.reachable.iter().any(|f| **f == "callback"));
+}
+
+#[test]
+fn sig_unknown_no_calls_o2() {
+    let module = signature("unknown-no-calls.c", 2);
+    let out = check(&module);
+    assert!(stores_top(&out));
+    assert!(!out.reachable.iter().any(|f| **f == "callback"));
+}
+
+#[test]
+fn sig_throws_o0() {
+    let module = signature("throws.cpp", 0);
+    let _out = check(&module);
+}
+
+#[test]
+fn sig_throws_o1() {
+    let module = signature("throws.cpp", 1);
+    let _out = check(&module);
+}
+
+#[test]
+fn sig_throws_o2() {
+    let module = signature("throws.cpp", 2);
+    let _out = check(&module);
+}
+
+// XFAIL(TODO)
+#[test]
+#[should_panic(expected = "Bug!")]
+fn sig_arg_memcpy_arg_o0() {
+    let module = signature("arg-memcpy-arg.c", 0);
+    let _out = check(&module);
+}
+
+#[test]
+fn sig_arg_memcpy_arg_o1() {
+    let module = signature("arg-memcpy-arg.c", 1);
+    let _out = check(&module);
+}
+
+#[test]
+fn sig_arg_memcpy_arg_o2() {
+    let module = signature("arg-memcpy-arg.c", 2);
+    let _out = check(&module);
+}
+
+#[test]
+fn sig_return_alloc_o0() {
+    let module = signature("return-alloc.c", 0);
+    let _out = check(&module);
+}
+
+#[test]
+fn sig_return_alloc_o1() {
+    let module = signature("return-alloc.c", 1);
+    let _out = check(&module);
+}
+
+#[test]
+fn sig_return_alloc_o2() {
+    let module = signature("return-alloc.c", 2);
+    let _out = check(&module);
+}
+
+#[test]
+fn sig_return_aliases_arg_o0() {
+    let module = signature("return-aliases-arg.c", 0);
+    let _out = check(&module);
+}
+
+#[test]
+fn sig_return_aliases_arg_o1() {
+    let module = signature("return-aliases-arg.c", 1);
+    let _out = check(&module);
+}
+
+#[test]
+fn sig_return_aliases_arg_o2() {
+    let module = signature("return-aliases-arg.c", 2);
+    let _out = check(&module);
+}
+
+#[test]
+fn sig_return_points_to_global_o0() {
+    let module = signature("return-points-to-global.c", 0);
+    let _out = check(&module);
+}
+
+#[test]
+fn sig_return_points_to_global_o1() {
+    let module = signature("return-points-to-global.c", 1);
+    let _out = check(&module);
 }
 
 #[test]
@@ -1065,6 +1841,186 @@ fn sig_return_points_to_global_o2() {
     let _out = check(&module);
 }
 
+#[test]
+fn sig_arg_alloc_o0() {
+    let module = signature("arg-alloc.c", 0);
+    let _out = check(&module);
+}
+
+#[test]
+fn sig_arg_alloc_o1() {
+    let module = signature("arg-alloc.c", 1);
+    let _out = check(&module);
+}
+
+#[test]
+fn sig_arg_alloc_o2() {
+    let module = signature("arg-alloc.c", 2);
+    let _out = check(&module);
+}
+
+#[test]
+fn sig_arg_alloc_once_o0() {
+    let module = signature("arg-alloc-once.c", 0);
+    let _out = check(&module);
+}
+
+#[test]
+fn sig_arg_alloc_once_o1() {
+    let module = signature("arg-alloc-once.c", 1);
+    let _out = check(&module);
+}
+
+#[test]
+fn sig_arg_alloc_once_o2() {
+    let module = signature("arg-alloc-once.c", 2);
+    let _out = check(&module);
+}
+
+#[test]
+fn sig_arg_memcpy_arg_reachable_o0() {
+    let module = signature("arg-memcpy-arg-reachable.c", 0);
+    let _out = check(&module);
+}
+
+#[test]
+fn sig_arg_memcpy_arg_reachable_o1() {
+    let module = signature("arg-memcpy-arg-reachable.c", 1);
+    let _out = check(&module);
+}
+
+#[test]
+fn sig_arg_memcpy_arg_reachable_o2() {
+    let module = signature("arg-memcpy-arg-reachable.c", 2);
+    let _out = check(&module);
+}
+
+#[test]
+fn sig_arg_memcpy_global_o0() {
+    let module = signature("arg-memcpy-global.c", 0);
+    let _out = check(&module);
+}
+
+#[test]
+fn sig_arg_memcpy_global_o1() {
+    let module = signature("arg-memcpy-global.c", 1);
+    let _out = check(&module);
+}
+
+#[test]
+fn sig_arg_memcpy_global_o2() {
+    let module = signature("arg-memcpy-global.c", 2);
+    let _out = check(&module);
+}
+
+#[test]
+fn sig_arg_memcpy_global_reachable_o0() {
+    let module = signature("arg-memcpy-global-reachable.c", 0);
+    let _out = check(&module);
+}
+
+#[test]
+fn sig_arg_memcpy_global_reachable_o1() {
+    let module = signature("arg-memcpy-global-reachable.c", 1);
+    let _out = check(&module);
+}
+
+#[test]
+fn sig_arg_memcpy_global_reachable_o2() {
+    let module = signature("arg-memcpy-global-reachable.c", 2);
+    let _out = check(&module);
+}
+
+#[test]
+fn sig_arg_points_to_global_o0() {
+    let module = signature("arg-points-to-global.c", 0);
+    let _out = check(&module);
+}
+
+#[test]
+fn sig_arg_points_to_global_o1() {
+    let module = signature("arg-points-to-global.c", 1);
+    let _out = check(&module);
+}
+
+#[test]
+fn sig_arg_points_to_global_o2() {
+    let module = signature("arg-points-to-global.c", 2);
+    let _out = check(&module);
+}
+
+#[test]
+fn sig_return_alloc_once_o0() {
+    let module = signature("return-alloc-once.c", 0);
+    let _out = check(&module);
+}
+
+#[test]
+fn sig_return_alloc_once_o1() {
+    let module = signature("return-alloc-once.c", 1);
+    let _out = check(&module);
+}
+
+#[test]
+fn sig_return_alloc_once_o2() {
+    let module = signature("return-alloc-once.c", 2);
+    let _out = check(&module);
+}
+
+#[test]
+fn sig_return_aliases_arg_reachable_o0() {
+    let module = signature("return-aliases-arg-reachable.c", 0);
+    let _out = check(&module);
+}
+
+#[test]
+fn sig_return_aliases_arg_reachable_o1() {
+    let module = signature("return-aliases-arg-reachable.c", 1);
+    let _out = check(&module);
+}
+
+#[test]
+fn sig_return_aliases_arg_reachable_o2() {
+    let module = signature("return-aliases-arg-reachable.c", 2);
+    let _out = check(&module);
+}
+
+#[test]
+fn sig_return_aliases_global_o0() {
+    let module = signature("return-aliases-global.c", 0);
+    let _out = check(&module);
+}
+
+#[test]
+fn sig_return_aliases_global_o1() {
+    let module = signature("return-aliases-global.c", 1);
+    let _out = check(&module);
+}
+
+#[test]
+fn sig_return_aliases_global_o2() {
+    let module = signature("return-aliases-global.c", 2);
+    let _out = check(&module);
+}
+
+#[test]
+fn sig_return_aliases_global_reachable_o0() {
+    let module = signature("return-aliases-global-reachable.c", 0);
+    let _out = check(&module);
+}
+
+#[test]
+fn sig_return_aliases_global_reachable_o1() {
+    let module = signature("return-aliases-global-reachable.c", 1);
+    let _out = check(&module);
+}
+
+#[test]
+fn sig_return_aliases_global_reachable_o2() {
+    let module = signature("return-aliases-global-reachable.c", 2);
+    let _out = check(&module);
+}
+
 #[test]
 fn sig_sig_o0() {
     let module = precise("sig-sig.c", 0);
@@ -1211,6 +2167,24 @@ fn stderr_o2() {
     let _out = check(&module);
 }
 
+#[test]
+fn struct_field_sensitive_o0() {
+    let module = precise("struct.c", 0);
+    let _out = check_field_sensitive(&module);
+}
+
+#[test]
+fn struct_field_sensitive_o1() {
+    let module = precise("struct.c", 1);
+    let _out = check_field_sensitive(&module);
+}
+
+#[test]
+fn struct_field_sensitive_o2() {
+    let module = precise("struct.c", 2);
+    let _out = check_field_sensitive(&module);
+}
+
 // TODO(#49)
 // #[test]
 // fn str_o0() {
@@ -1614,6 +2588,48 @@ fn template_vector_push_back_o2() {
     let _out = check(&module);
 }
 
+#[test]
+fn struct_return_o0() {
+    let module = sound("struct-return.c", 0);
+    let _out = check(&module);
+}
+
+#[test]
+fn struct_return_o1() {
+    let module = sound("struct-return.c", 1);
+    let _out = check(&module);
+}
+
+#[test]
+fn struct_return_o2() {
+    let module = sound("struct-return.c", 2);
+    let _out = check(&module);
+}
+
+#[test]
+fn vectors_o0() {
+    let module = sound("vectors.c", 0);
+    let _out = check(&module);
+}
+
+#[test]
+fn vectors_o1() {
+    let module = sound("vectors.c", 1);
+    let _out = check(&module);
+}
+
+#[test]
+fn vectors_o2() {
+    let module = sound("vectors.c", 2);
+    let _out = check(&module);
+}
+
+#[test]
+fn throw_o0() {
+    let module = sound("throw.cpp", 0);
+    let _out = check(&module);
+}
+
 #[test]
 fn throw_o1() {
     let module = sound("throw.cpp", 1);
@@ -1626,6 +2642,110 @@ fn throw_o2() {
     let _out = check(&module);
 }
 
+/// Check that the exception thrown by `must_throw` is caught by `catch_test`,
+/// and that `main` catches the exceptions thrown by the (external)
+/// `std::string::at`
+fn check_exceptions(module: &NamedModule) {
+    let out = check(module);
+    assert!(out.needs_signature.is_empty(), "{:?}", out.needs_signature);
+    let m = &module.module;
+    let thrown = arg_points_to(&out, m, "_Z10must_throwv", "__cxa_throw", 0);
+    assert!(!thrown.is_empty());
+    assert!(!thrown.contains(&Alloc::Top));
+    let caught = arg_points_to(&out, m, "_Z10catch_testii", "__cxa_begin_catch", 0);
+    assert!(thrown.is_subset(&caught));
+    let from_extern = arg_points_to(&out, m, "main", "__cxa_begin_catch", 0);
+    assert!(from_extern.contains(&Alloc::Top));
+}
+
+#[test]
+fn exceptions_o0() {
+    let module = property("exceptions.cpp", 0);
+    check_exceptions(&module);
+}
+
+#[test]
+fn exceptions_o1() {
+    let module = property("exceptions.cpp", 1);
+    check_exceptions(&module);
+}
+
+#[test]
+fn exceptions_o2() {
+    let module = property("exceptions.cpp", 2);
+    check_exceptions(&module);
+}
+
+#[test]
+fn summary_lib_o0() {
+    let module = summarized("lib.c", 0);
+    check_summary(&module);
+}
+
+#[test]
+fn summary_lib_o1() {
+    let module = summarized("lib.c", 1);
+    check_summary(&module);
+}
+
+#[test]
+fn summary_lib_o2() {
+    let module = summarized("lib.c", 2);
+    check_summary(&module);
+}
+
+/// Check that calls through the alias `@bar` resolve to its aliasee `@foo`
+fn check_symbol_alias(module: &NamedModule, opt: u8) {
+    let out = check(module);
+    assert!(out.needs_signature.is_empty(), "{:?}", out.needs_signature);
+    let mut through_alias = 0;
+    for f in module.module.functions.values() {
+        for b in &f.blocks {
+            for i in &b.instrs {
+                if let Opcode::Call(Call {
+                    callee: Callee::Operand(op),
+                    ..
+                }) = i.opcode.as_ref()
+                {
+                    if let Operand::Constant(c) = &**op {
+                        if let Constant::Global(g) = &**c {
+                            if g.to_string() == "@bar" {
+                                through_alias += 1;
+                                assert!(points_to(&out, op).iter().any(|a| matches!(
+                                    a,
+                                    Alloc::Function(f) if *f.function_name() == "foo"
+                                )));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+    // Optimizations may replace uses of the alias with its aliasee
+    if opt == 0 {
+        assert!(through_alias > 0);
+    }
+}
+
+#[test]
+fn symbol_alias_o0() {
+    let module = property("symbol-alias.c", 0);
+    check_symbol_alias(&module, 0);
+}
+
+#[test]
+fn symbol_alias_o1() {
+    let module = property("symbol-alias.c", 1);
+    check_symbol_alias(&module, 1);
+}
+
+#[test]
+fn symbol_alias_o2() {
+    let module = property("symbol-alias.c", 2);
+    check_symbol_alias(&module, 2);
+}
+
 #[test]
 fn undef_o0() {
     let module = sound("undef.c", 0);
@@ -1644,17 +2764,102 @@ fn undef_o2() {
     let _out = check(&module);
 }
 
-// XFAIL(TODO): Can't handle exceptions yet
+const STEENSGAARD_PROGRAMS: &[&str] = &[
+    "call.c",
+    "func-ptr.c",
+    "function-table.c",
+    "global-struct.c",
+    "indirect-call.c",
+    "malloc.c",
+    "memcpy.c",
+    "ptr-ret.c",
+    "varargs.c",
+];
+
+#[test]
+fn steensgaard_o0() {
+    for program in STEENSGAARD_PROGRAMS {
+        check_steensgaard(&sound(program, 0));
+    }
+}
+
+#[test]
+fn steensgaard_o1() {
+    for program in STEENSGAARD_PROGRAMS {
+        check_steensgaard(&sound(program, 1));
+    }
+}
+
+#[test]
+fn steensgaard_o2() {
+    for program in STEENSGAARD_PROGRAMS {
+        check_steensgaard(&sound(program, 2));
+    }
+}
+
+#[test]
+fn varargs_o0() {
+    let module = sound("varargs.c", 0);
+    let _out = check(&module);
+}
+
 #[test]
-#[should_panic(expected = "assertion failed: points_to_something")]
 fn varargs_o1() {
     let module = sound("varargs.c", 1);
     let _out = check(&module);
 }
 
 #[test]
-#[should_panic(expected = "assertion failed: points_to_something")]
 fn varargs_o2() {
     let module = sound("varargs.c", 2);
     let _out = check(&module);
 }
+
+#[test]
+fn widen_o0() {
+    let module = sound("widen.c", 0);
+    let out = check_widened(&module, widen::Summary::Top);
+    assert!(out.metrics.unwrap().widened > 0);
+}
+
+#[test]
+fn widen_o1() {
+    let module = sound("widen.c", 1);
+    let out = check_widened(&module, widen::Summary::Top);
+    assert!(out.metrics.unwrap().widened > 0);
+}
+
+#[test]
+fn widen_o2() {
+    let module = sound("widen.c", 2);
+    let out = check_widened(&module, widen::Summary::Top);
+    assert!(out.metrics.unwrap().widened > 0);
+}
+
+#[test]
+fn widen_type_o1() {
+    let module = sound("widen.c", 1);
+    let out = check_widened(&module, widen::Summary::Type);
+    assert!(out.metrics.unwrap().widened > 0);
+}
+
+#[test]
+fn widen_alias_o0() {
+    let module = sound("widen-alias.c", 0);
+    check_widened_alias(&module, widen::Summary::Top);
+    check_widened_alias(&module, widen::Summary::Type);
+}
+
+#[test]
+fn widen_alias_o1() {
+    let module = sound("widen-alias.c", 1);
+    check_widened_alias(&module, widen::Summary::Top);
+    check_widened_alias(&module, widen::Summary::Type);
+}
+
+#[test]
+fn widen_alias_o2() {
+    let module = sound("widen-alias.c", 2);
+    check_widened_alias(&module, widen::Summary::Top);
+    check_widened_alias(&module, widen::Summary::Type);
+}
@@ -0,0 +1,12 @@
+#include <stdlib.h>
+
+#include "assert.h"
+
+void *__attribute__((noinline)) xmalloc(size_t n) { return malloc(n); }
+
+int main() {
+  int **p = xmalloc(sizeof(int *));
+  int **q = xmalloc(sizeof(int *));
+  assert_disjoint(p, q);
+  return 0;
+}
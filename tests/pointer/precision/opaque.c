@@ -0,0 +1,15 @@
+// RUN: clang -o - -emit-llvm -S -O1 %s 2>&1 | FileCheck %s
+
+#include "assert.h"
+
+extern int *source(void);
+
+int main() {
+  // CHECK: call {{.+}} @source
+  int *x = source();
+  // CHECK: call {{.+}} @source
+  int *y = source();
+  // CHECK: call {{.+}} @assert
+  assert_disjoint(x, y);
+  return 0;
+}
@@ -0,0 +1,31 @@
+#include "assert.h"
+
+class Box {
+public:
+  int *contents;
+  void __attribute__((noinline)) set(int *p) { contents = p; }
+  int *__attribute__((noinline)) get() { return contents; }
+};
+
+// With call-site sensitivity, both calls to `Box::set` share the context of
+// this call site.
+void __attribute__((noinline)) init(Box *b, int *p) { b->set(p); }
+
+int *__attribute__((noinline)) first(int *x) {
+  Box a;
+  init(&a, x);
+  return a.get();
+}
+
+int *__attribute__((noinline)) second(int *y) {
+  Box b;
+  init(&b, y);
+  return b.get();
+}
+
+int main() {
+  int x;
+  int y;
+  assert_disjoint(first(&x), second(&y));
+  return 0;
+}
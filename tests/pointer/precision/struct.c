@@ -0,0 +1,12 @@
+#include "assert.h"
+
+int main() {
+  int u;
+  int v;
+  struct {
+    int *x;
+    int *y;
+  } pt = {&u, &v};
+  assert_disjoint(&pt.x, &pt.y);
+  assert_disjoint(pt.x, pt.y);
+}
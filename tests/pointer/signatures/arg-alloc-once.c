@@ -0,0 +1,12 @@
+#include "assert.h"
+
+extern int arg_alloc_once(void **);
+
+int main() {
+  void *p;
+  void *q;
+  arg_alloc_once(&p);
+  arg_alloc_once(&q);
+  assert_points_to_something(p);
+  assert_may_alias(p, q);
+}
@@ -0,0 +1,9 @@
+#include "assert.h"
+
+extern int arg_alloc(void **);
+
+int main() {
+  void *p;
+  arg_alloc(&p);
+  assert_points_to_something(p);
+}
@@ -0,0 +1,16 @@
+#include <stdlib.h>
+
+#include "assert.h"
+
+extern void arg_memcpy_arg_reachable(void *dst, void *src);
+
+int main() {
+  char c;
+  char *pc = &c;
+  char ***src = malloc(sizeof(char **));
+  char ***dst = malloc(sizeof(char **));
+  *src = &pc;
+  arg_memcpy_arg_reachable(dst, src);
+  assert_may_alias(*dst, &pc);
+  assert_may_alias(*dst, &c);
+}
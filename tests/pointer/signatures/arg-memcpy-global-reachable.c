@@ -0,0 +1,14 @@
+#include "assert.h"
+
+extern void arg_memcpy_global_reachable(void *dst);
+
+char g;
+char *pg = &g;
+char **global = &pg;
+
+int main() {
+  char **p;
+  arg_memcpy_global_reachable(&p);
+  assert_may_alias(p, &pg);
+  assert_may_alias(p, &g);
+}
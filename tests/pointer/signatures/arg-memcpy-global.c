@@ -0,0 +1,12 @@
+#include "assert.h"
+
+extern void arg_memcpy_global(void *dst);
+
+char g;
+char *global = &g;
+
+int main() {
+  char *p;
+  arg_memcpy_global(&p);
+  assert_may_alias(p, &g);
+}
@@ -0,0 +1,11 @@
+#include "assert.h"
+
+extern void arg_points_to_global(char **);
+
+char g;
+
+int main() {
+  char *p;
+  arg_points_to_global(&p);
+  assert_may_alias(p, &g);
+}
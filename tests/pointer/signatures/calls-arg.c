@@ -0,0 +1,15 @@
+#include <stdlib.h>
+
+#include "assert.h"
+
+extern int calls_arg(void *(*)(void *), void *);
+
+__attribute__((noinline)) void *callback(void *p) {
+  assert_points_to_something(p);
+  return p;
+}
+
+int main() {
+  void *p = malloc(1);
+  return calls_arg(callback, p);
+}
@@ -0,0 +1,5 @@
+#include "assert.h"
+
+extern char *global;
+
+int main() { assert_points_to_something(global); }
@@ -0,0 +1,18 @@
+#include "assert.h"
+
+struct node {
+  struct node *next;
+  char *data;
+};
+
+extern void *return_aliases_arg_reachable(struct node *);
+
+char g;
+
+int main() {
+  struct node tail = {0, &g};
+  struct node head = {&tail, 0};
+  void *p = return_aliases_arg_reachable(&head);
+  assert_may_alias(p, &head);
+  assert_may_alias(p, &g);
+}
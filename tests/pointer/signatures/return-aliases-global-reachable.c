@@ -0,0 +1,13 @@
+#include "assert.h"
+
+extern void *return_aliases_global_reachable(void);
+
+char g;
+char *pg = &g;
+char **global = &pg;
+
+int main() {
+  void *p = return_aliases_global_reachable();
+  assert_may_alias(p, &pg);
+  assert_may_alias(p, &g);
+}
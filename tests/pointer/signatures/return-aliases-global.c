@@ -0,0 +1,11 @@
+#include "assert.h"
+
+extern void *return_aliases_global(void);
+
+char g;
+char *global = &g;
+
+int main() {
+  void *p = return_aliases_global();
+  assert_may_alias(p, &g);
+}
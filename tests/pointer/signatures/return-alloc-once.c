@@ -0,0 +1,10 @@
+#include "assert.h"
+
+extern void *return_alloc_once(void);
+
+int main() {
+  void *p = return_alloc_once();
+  void *q = return_alloc_once();
+  assert_points_to_something(p);
+  assert_may_alias(p, q);
+}
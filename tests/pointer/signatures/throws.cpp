@@ -0,0 +1,14 @@
+#include <exception>
+
+#include "assert.h"
+
+extern "C" void may_throw(void);
+
+int main() {
+  try {
+    may_throw();
+  } catch (const std::exception &e) {
+    assert_points_to_something((void *)&e);
+  }
+  return 0;
+}
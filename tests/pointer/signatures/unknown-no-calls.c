@@ -0,0 +1,14 @@
+#include <stdlib.h>
+
+#include "assert.h"
+
+extern void unknown(int **, void (*)(int *));
+
+__attribute__((noinline)) void callback(int *p) { *p = 0; }
+
+int main() {
+  int **pp = malloc(sizeof(int *));
+  unknown(pp, callback);
+  assert_points_to_something(*pp);
+  return 0;
+}
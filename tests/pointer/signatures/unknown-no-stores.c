@@ -0,0 +1,15 @@
+#include "assert.h"
+
+extern void unknown(int **, void (*)(int *));
+
+__attribute__((noinline)) void callback(int *p) {
+  assert_points_to_something(p);
+}
+
+int main() {
+  int x = 0;
+  int *p = &x;
+  unknown(&p, callback);
+  assert_points_to_something(p);
+  return 0;
+}
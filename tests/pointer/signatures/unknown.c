@@ -0,0 +1,14 @@
+#include <stdlib.h>
+
+#include "assert.h"
+
+extern int unknown(void (*)(void *), void *);
+
+__attribute__((noinline)) void callback(void *p) {
+  assert_points_to_something(p);
+}
+
+int main() {
+  void *p = malloc(1);
+  return unknown(callback, p);
+}
@@ -0,0 +1,19 @@
+// RUN: clang -o - -emit-llvm -S -O1 %s 2>&1 | FileCheck %s
+
+#include <stdlib.h>
+
+#include "assert.h"
+
+int main() {
+  // CHECK: call {{.+}} @malloc
+  char *m = malloc(16);
+  // CHECK: call {{.+}} @calloc
+  char *c = calloc(3, 8);
+  // CHECK: call {{.+}} @reallocarray
+  char *r = reallocarray(NULL, 7, 8);
+  // CHECK: call {{.+}} @assert
+  assert_points_to_something(m);
+  assert_points_to_something(c);
+  assert_points_to_something(r);
+  return 0;
+}
@@ -0,0 +1,19 @@
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+extern "C" {
+    fn assert_points_to_something(p: *const i32);
+    fn assert_may_alias(p: *const i32, q: *const i32);
+}
+
+fn main() {
+    let mut i = 1;
+    let mut j = 2;
+    let ptr = AtomicPtr::new(&mut i as *mut i32);
+    let old = ptr.swap(&mut j, Ordering::SeqCst);
+    unsafe { assert_points_to_something(old) }
+    unsafe { assert_may_alias(old, &i) }
+    let _ = ptr.compare_exchange(&mut j, old, Ordering::SeqCst, Ordering::SeqCst);
+    let new = ptr.load(Ordering::SeqCst);
+    unsafe { assert_points_to_something(new) }
+    unsafe { assert_may_alias(new, &j) }
+}
@@ -0,0 +1,33 @@
+// RUN: clang -o - -emit-llvm -S -O1 %s 2>&1 | FileCheck %s
+
+#include <stdatomic.h>
+#include <stddef.h>
+
+#include "assert.h"
+
+struct node {
+  struct node *next;
+};
+
+_Atomic(struct node *) head = NULL;
+
+void push(struct node *n) {
+  struct node *old = atomic_load(&head);
+  do {
+    n->next = old;
+    // CHECK: cmpxchg
+  } while (!atomic_compare_exchange_weak(&head, &old, n));
+}
+
+int main() {
+  struct node a;
+  struct node b;
+  push(&a);
+  push(&b);
+  // CHECK: atomicrmw xchg
+  struct node *top = atomic_exchange(&head, NULL);
+  // CHECK: call {{.+}} @assert
+  assert_points_to_something(top);
+  assert_points_to_something(top->next);
+  return 0;
+}
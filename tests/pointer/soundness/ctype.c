@@ -0,0 +1,14 @@
+// RUN: clang -o - -emit-llvm -S -O1 %s 2>&1 | FileCheck %s
+
+#include <ctype.h>
+
+#include "assert.h"
+
+int main() {
+  // CHECK: call {{.+}} @__ctype_b_loc
+  const unsigned short **table = __ctype_b_loc();
+  // CHECK: call {{.+}} @assert
+  assert_points_to_something(table);
+  assert_points_to_something((void *)*table);
+  return 0;
+}
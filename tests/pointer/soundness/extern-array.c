@@ -0,0 +1,16 @@
+#include <string.h>
+
+#include "assert.h"
+
+// Declared with type `[0 x ptr]`, but defined elsewhere with some size
+extern char *buf[];
+
+int main() {
+  char c;
+  char *p = &c;
+  memcpy(buf, &p, sizeof(char *));
+  char *q;
+  memcpy(&q, buf, sizeof(char *));
+  assert_points_to_something(q);
+  return 0;
+}
@@ -0,0 +1,20 @@
+struct s {
+  char c;
+  void *p;
+  int i;
+};
+
+struct __attribute__((packed)) ps {
+  char c;
+  void *p;
+};
+
+struct empty {};
+
+struct s s;
+struct ps ps;
+char arr[3][5];
+struct empty empty;
+extern char ext[4];
+
+int main() { return ext[0] + (&empty != 0); }
@@ -0,0 +1,30 @@
+// RUN: clang -o - -emit-llvm -S -O1 %s 2>&1 | FileCheck %s
+
+#include "assert.h"
+
+// Called from many sites, so analyzed without contexts
+int *__attribute__((noinline)) popular(int *p) {
+  // CHECK: ret
+  return p;
+}
+
+// Called from one site, so keeps its contexts
+int *__attribute__((noinline)) cheap(int *p) {
+  // CHECK: ret
+  return p;
+}
+
+int main() {
+  int a, b, c;
+  // CHECK: call {{.+}} @popular
+  int *x = popular(&a);
+  // CHECK: call {{.+}} @popular
+  int *y = popular(&b);
+  // CHECK: call {{.+}} @popular
+  int *z = popular(&c);
+  // CHECK: call {{.+}} @cheap
+  int *w = cheap(&a);
+  // CHECK: call {{.+}} @assert
+  assert_points_to_something(x, y, z, w);
+  return 0;
+}
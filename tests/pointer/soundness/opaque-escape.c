@@ -0,0 +1,16 @@
+// RUN: clang -o - -emit-llvm -S -O1 %s 2>&1 | FileCheck %s
+
+#include "assert.h"
+
+extern void *source(void);
+extern void sink(void *p);
+
+int main() {
+  // CHECK: call {{.+}} @source
+  int **x = source();
+  // CHECK: call {{.+}} @sink
+  sink(x);
+  // CHECK: call {{.+}} @assert
+  assert_points_to_something(*x);
+  return 0;
+}
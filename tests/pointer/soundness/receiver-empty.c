@@ -0,0 +1,23 @@
+// RUN: clang -o - -emit-llvm -S -O1 %s 2>&1 | FileCheck %s
+
+#include <stdlib.h>
+
+#include "assert.h"
+
+int *__attribute__((noinline)) second(int *unused, int *p) {
+  // CHECK: call {{.+}} @assert
+  assert_reachable();
+  return p;
+}
+
+int main() {
+  int x;
+  // CHECK: call {{.+}} @malloc
+  int **q = malloc(sizeof(int *));
+  // The receiver points to nothing
+  // CHECK: call {{.+}} @second
+  int *r = second(*q, &x);
+  // CHECK: call {{.+}} @assert
+  assert_points_to_something(r);
+  return 0;
+}
@@ -0,0 +1,25 @@
+// RUN: clang -o - -emit-llvm -S -O1 %s 2>&1 | FileCheck %s
+
+#include "assert.h"
+
+struct pair {
+  int *fst;
+  int *snd;
+};
+
+__attribute__((noinline)) struct pair make_pair(int *fst, int *snd) {
+  // CHECK: insertvalue
+  struct pair p = {fst, snd};
+  return p;
+}
+
+int main() {
+  int x = 0;
+  int y = 1;
+  // CHECK: extractvalue
+  struct pair p = make_pair(&x, &y);
+  // CHECK: call {{.+}} @assert
+  assert_points_to_something(p.fst);
+  assert_points_to_something(p.snd);
+  return *p.fst + *p.snd;
+}
@@ -0,0 +1,22 @@
+// RUN: clang -o - -emit-llvm -S -O1 %s 2>&1 | FileCheck %s
+
+#include <stdlib.h>
+
+#include "assert.h"
+
+// No signature
+extern void unknown(int **, void (*)(int *));
+
+__attribute__((noinline)) void callback(int *p) {
+  // CHECK: call {{.+}} @assert
+  assert_points_to_something(p);
+}
+
+int main() {
+  // CHECK: call {{.+}} @malloc
+  int **pp = malloc(sizeof(int *));
+  // CHECK: call {{.+}} @unknown
+  unknown(pp, callback);
+  assert_points_to_something(*pp);
+  return 0;
+}
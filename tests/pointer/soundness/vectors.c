@@ -0,0 +1,23 @@
+// RUN: clang -o - -emit-llvm -S -O2 %s 2>&1 | FileCheck %s
+
+#include "assert.h"
+
+__attribute__((noinline)) void copy(int **restrict dst, int **restrict src) {
+  // CHECK: load <2 x i32*>
+  dst[0] = src[0];
+  dst[1] = src[1];
+  dst[2] = src[2];
+  dst[3] = src[3];
+}
+
+int main() {
+  int x = 0;
+  int y = 1;
+  int *src[4] = {&x, &y, &x, &y};
+  int *dst[4];
+  copy(dst, src);
+  // CHECK: call {{.+}} @assert
+  assert_points_to_something(dst[0]);
+  assert_points_to_something(dst[3]);
+  return *dst[0] + *dst[3];
+}
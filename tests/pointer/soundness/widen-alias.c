@@ -0,0 +1,20 @@
+#include <stdlib.h>
+
+#include "assert.h"
+
+int **slot;
+
+__attribute__((noinline)) void put(int **p) { slot = p; }
+
+int main() {
+  int x = 0;
+  int **p = malloc(sizeof(int *));
+  put(malloc(sizeof(int *)));
+  put(malloc(sizeof(int *)));
+  put(p);
+  // `slot` may be widened, but the store through it must still reach `*p`
+  *slot = &x;
+  int *q = *p;
+  assert_points_to_something(q);
+  return 0;
+}
@@ -0,0 +1,15 @@
+#include <stdlib.h>
+
+#include "assert.h"
+
+void *slot;
+
+__attribute__((noinline)) void put(void *p) { slot = p; }
+
+int main() {
+  put(malloc(1));
+  put(malloc(2));
+  put(malloc(3));
+  assert_points_to_something(slot);
+  return 0;
+}
@@ -0,0 +1,39 @@
+#include <stdlib.h>
+
+int table;
+
+char *id(char *p, long n) { return p; }
+
+char *mk(long n) { return malloc(n); }
+
+char *deref(char **pp) { return *pp; }
+
+void copy(char **dst, char **src) { *dst = *src; }
+
+void out_alloc(char **out) { *out = malloc(8); }
+
+int *get_table(void) { return &table; }
+
+void set_table(int **out) { *out = &table; }
+
+struct s {
+  char *p;
+};
+
+void set(struct s *s, char *p) { s->p = p; }
+
+static void helper(void (*cb)(char *), char *arg) { cb(arg); }
+
+void apply(void (*cb)(char *), char *arg) { helper(cb, arg); }
+
+int pure(int x) { return x; }
+
+typedef void (*handler_t)(void);
+
+static void on_event(void) {}
+
+handler_t get_handler(void) { return on_event; }
+
+extern char *lookup(const char *key);
+
+void get(char **out) { *out = lookup("X"); }